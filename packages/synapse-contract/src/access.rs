@@ -0,0 +1,90 @@
+use soroban_sdk::{Address, BytesN, Env};
+
+use crate::errors::SynapseError;
+use crate::plan::{get_status, plan_exists, TTL_HOT};
+use crate::storage_keys::DataKey;
+use crate::subscription::get_active_pass;
+use crate::types::{Entitlement, LicenseModel, PlanStatus};
+
+/// The buyer's entitlement to a plan, whether or not it has expired.
+pub fn get_entitlement(env: &Env, plan_id: &BytesN<16>, buyer: &Address) -> Option<Entitlement> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Access(plan_id.clone(), buyer.clone()))
+}
+
+/// Whether `buyer` can currently access the plan, through an entitlement or
+/// an active subscription pass. A pass covers only listed plans.
+pub fn has_access(env: &Env, plan_id: &BytesN<16>, buyer: &Address) -> bool {
+    if get_active_pass(env, buyer).is_some()
+        && plan_exists(env, plan_id)
+        && get_status(env, plan_id) == PlanStatus::Active
+    {
+        return true;
+    }
+    match get_entitlement(env, plan_id, buyer) {
+        Some(entitlement) => match entitlement.expires_at_ledger {
+            Some(expires_at) => env.ledger().sequence() < expires_at,
+            None => true,
+        },
+        None => false,
+    }
+}
+
+/// Whether the buyer holds a perpetual entitlement to the plan.
+pub fn owns_outright(env: &Env, plan_id: &BytesN<16>, buyer: &Address) -> bool {
+    matches!(
+        get_entitlement(env, plan_id, buyer),
+        Some(Entitlement {
+            expires_at_ledger: None,
+            ..
+        })
+    )
+}
+
+/// Grants or renews the buyer's access under `license`. A renewal extends a
+/// time-limited entitlement from its current expiry, or from now if it has
+/// lapsed. Perpetual owners cannot be charged again.
+pub fn grant_access(
+    env: &Env,
+    plan_id: &BytesN<16>,
+    buyer: &Address,
+    license: &LicenseModel,
+) -> Result<Entitlement, SynapseError> {
+    let now = env.ledger().sequence();
+    let current = get_entitlement(env, plan_id, buyer);
+    let expires_at_ledger = match (license, &current) {
+        (
+            _,
+            Some(Entitlement {
+                expires_at_ledger: None,
+                ..
+            }),
+        ) => return Err(SynapseError::AlreadyPurchased),
+        (LicenseModel::Perpetual, _) => None,
+        (LicenseModel::Duration(ledgers), current) => {
+            let from = match current {
+                Some(Entitlement {
+                    expires_at_ledger: Some(expires_at),
+                    ..
+                }) if *expires_at > now => *expires_at,
+                _ => now,
+            };
+            Some(
+                from.checked_add(*ledgers)
+                    .ok_or(SynapseError::ArithmeticOverflow)?,
+            )
+        }
+    };
+
+    let entitlement = Entitlement {
+        granted_at_ledger: now,
+        expires_at_ledger,
+    };
+    let key = DataKey::Access(plan_id.clone(), buyer.clone());
+    env.storage().persistent().set(&key, &entitlement);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_HOT, TTL_HOT);
+    Ok(entitlement)
+}
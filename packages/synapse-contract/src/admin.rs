@@ -1,30 +1,104 @@
 use soroban_sdk::{Address, Env};
 
+use crate::errors::SynapseError;
 use crate::storage_keys::DataKey;
+use crate::types::DustRecipient;
 
-pub fn require_admin(env: &Env) -> Address {
-    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+/// Default price bounds in stroops, used until the admin configures them.
+const DEFAULT_MIN_PRICE: i128 = 1; // 1 stroop
+const DEFAULT_MAX_PRICE: i128 = 1_000_000_000; // 100 XLM
+
+pub fn get_admin(env: &Env) -> Result<Address, SynapseError> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(SynapseError::NotInitialized)
+}
+
+pub fn require_admin(env: &Env) -> Result<Address, SynapseError> {
+    let admin = get_admin(env)?;
     admin.require_auth();
-    admin
+    Ok(admin)
+}
+
+pub fn get_pending_admin(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::PendingAdmin)
 }
 
-pub fn get_operator(env: &Env) -> Address {
+pub fn set_pending_admin(env: &Env, new_admin: &Address) {
+    env.storage()
+        .instance()
+        .set(&DataKey::PendingAdmin, new_admin);
+}
+
+/// Promotes the pending admin, clearing the proposal. Returns (old, new).
+pub fn take_pending_admin(env: &Env) -> Result<(Address, Address), SynapseError> {
+    let old_admin = get_admin(env)?;
+    let new_admin = get_pending_admin(env).ok_or(SynapseError::NoPendingAdmin)?;
+    new_admin.require_auth();
+
+    env.storage().instance().set(&DataKey::Admin, &new_admin);
+    env.storage().instance().remove(&DataKey::PendingAdmin);
+    Ok((old_admin, new_admin))
+}
+
+pub fn get_operator(env: &Env) -> Result<Address, SynapseError> {
     env.storage()
         .instance()
         .get(&DataKey::OperatorAddress)
-        .unwrap()
+        .ok_or(SynapseError::NotInitialized)
+}
+
+pub fn get_contributor_share_bps(env: &Env) -> Result<u32, SynapseError> {
+    env.storage()
+        .instance()
+        .get(&DataKey::ContributorShareBps)
+        .ok_or(SynapseError::NotInitialized)
+}
+
+/// Returns the (floor, ceiling) a plan price must fall within.
+pub fn get_price_bounds(env: &Env) -> (i128, i128) {
+    let min: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MinPrice)
+        .unwrap_or(DEFAULT_MIN_PRICE);
+    let max: i128 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxPrice)
+        .unwrap_or(DEFAULT_MAX_PRICE);
+    (min, max)
+}
+
+/// Defaults to the operator, matching the original floor-division split.
+pub fn get_dust_recipient(env: &Env) -> DustRecipient {
+    env.storage()
+        .instance()
+        .get(&DataKey::DustRecipient)
+        .unwrap_or(DustRecipient::Operator)
+}
+
+/// Slice of the operator share paid to referrers. Defaults to none.
+pub fn get_referral_share_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ReferralShareBps)
+        .unwrap_or(0)
 }
 
-pub fn get_contributor_share_pct(env: &Env) -> u32 {
+/// Slice of a derivative's contributor share paid to its parent's
+/// contributor. Defaults to none.
+pub fn get_royalty_bps(env: &Env) -> u32 {
     env.storage()
         .instance()
-        .get(&DataKey::ContributorSharePct)
-        .unwrap()
+        .get(&DataKey::RoyaltyBps)
+        .unwrap_or(0)
 }
 
-pub fn get_native_token(env: &Env) -> Address {
+pub fn get_native_token(env: &Env) -> Result<Address, SynapseError> {
     env.storage()
         .instance()
         .get(&DataKey::NativeTokenAddress)
-        .unwrap()
+        .ok_or(SynapseError::NotInitialized)
 }
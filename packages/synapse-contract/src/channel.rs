@@ -0,0 +1,195 @@
+use soroban_sdk::{token, xdr::ToXdr, Address, BytesN, Env, Vec};
+
+use crate::access::owns_outright;
+use crate::admin::get_operator;
+use crate::errors::SynapseError;
+use crate::events::emit_plan_purchased;
+use crate::plan::{get_license, load_plan};
+use crate::purchase::settle_purchase;
+use crate::storage_keys::DataKey;
+use crate::tokens::{get_plan_price, require_accepted};
+use crate::types::{Channel, LicenseModel, PlanMeta, PurchaseRecord, Voucher};
+
+/// Ledgers a closing channel stays open for payees to redeem their latest
+/// voucher (~1 day at ~5s/ledger).
+pub const CHANNEL_DISPUTE_WINDOW: u32 = 17_280;
+/// Minimum TTL kept on an open channel, in ledgers (~31 days).
+const CHANNEL_TTL: u32 = 535_680;
+
+pub fn get_channel(env: &Env, buyer: &Address, token: &Address) -> Option<Channel> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Channel(buyer.clone(), token.clone()))
+}
+
+fn load_channel(env: &Env, buyer: &Address, token: &Address) -> Result<Channel, SynapseError> {
+    get_channel(env, buyer, token).ok_or(SynapseError::ChannelNotFound)
+}
+
+fn save_channel(env: &Env, buyer: &Address, token: &Address, channel: &Channel) {
+    let key = DataKey::Channel(buyer.clone(), token.clone());
+    env.storage().persistent().set(&key, channel);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, CHANNEL_TTL, CHANNEL_TTL);
+}
+
+/// Escrows `deposit` from the buyer's wallet and opens a channel whose
+/// vouchers must be signed by `signer`. One channel per buyer and token.
+pub fn open_channel(
+    env: &Env,
+    buyer: &Address,
+    token: &Address,
+    signer: &BytesN<32>,
+    deposit: i128,
+) -> Result<Channel, SynapseError> {
+    buyer.require_auth();
+    require_accepted(env, token)?;
+    if deposit <= 0 {
+        return Err(SynapseError::InvalidAmount);
+    }
+    if get_channel(env, buyer, token).is_some() {
+        return Err(SynapseError::ChannelExists);
+    }
+
+    // Channel IDs are never reused, so vouchers for a closed channel
+    // cannot be replayed against a new one.
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::ChannelCount)
+        .unwrap_or(0)
+        + 1;
+    env.storage().instance().set(&DataKey::ChannelCount, &id);
+
+    token::Client::new(env, token).transfer(buyer, &env.current_contract_address(), &deposit);
+
+    let channel = Channel {
+        id,
+        signer: signer.clone(),
+        deposit,
+        redeemed: 0,
+        settled: 0,
+        nonce: 0,
+        closes_at: None,
+    };
+    save_channel(env, buyer, token, &channel);
+    Ok(channel)
+}
+
+/// Settles the plans a voucher adds since the last redemption, charging each
+/// at its listed price in the channel token. Plans the buyer owns outright
+/// are not charged again, nor is a perpetual plan the voucher lists twice;
+/// time-limited licenses are renewed once per listing. Plans delisted after
+/// the voucher was signed are still settled, so one delisting cannot stall
+/// the channel. The charge must fit within the voucher's running total.
+/// Returns the purchase records created.
+pub fn redeem_voucher(
+    env: &Env,
+    redeemer: &Address,
+    voucher: &Voucher,
+    signature: &BytesN<64>,
+) -> Result<Vec<PurchaseRecord>, SynapseError> {
+    redeemer.require_auth();
+
+    let buyer = &voucher.buyer;
+    let token = &voucher.token;
+    let mut channel = load_channel(env, buyer, token)?;
+    if let Some(closes_at) = channel.closes_at {
+        if env.ledger().sequence() > closes_at {
+            return Err(SynapseError::ChannelClosed);
+        }
+    }
+    if voucher.contract != env.current_contract_address()
+        || voucher.channel_id != channel.id
+        || voucher.nonce <= channel.nonce
+        || voucher.plan_ids.len() < channel.settled
+        || voucher.total < channel.redeemed
+    {
+        return Err(SynapseError::InvalidVoucher);
+    }
+    if voucher.total > channel.deposit {
+        return Err(SynapseError::InsufficientBalance);
+    }
+
+    // Traps if the signature does not match
+    env.crypto()
+        .ed25519_verify(&channel.signer, &voucher.clone().to_xdr(env), signature);
+
+    let operator = get_operator(env)?;
+    let mut authorized = *redeemer == operator;
+    let mut plans: Vec<(PlanMeta, i128)> = Vec::new(env);
+    let mut charge: i128 = 0;
+    for i in channel.settled..voucher.plan_ids.len() {
+        let plan = load_plan(env, &voucher.plan_ids.get_unchecked(i))?;
+        authorized |= *redeemer == plan.contributor;
+        // Owned plans are settled without a charge; the buyer keeps access
+        let perpetual = get_license(env, &plan.id) == LicenseModel::Perpetual;
+        let listed = plans.iter().any(|(p, _)| p.id == plan.id);
+        if owns_outright(env, &plan.id, buyer) || (perpetual && listed) {
+            continue;
+        }
+        let price = get_plan_price(env, &plan, token)?;
+        charge = charge
+            .checked_add(price)
+            .ok_or(SynapseError::ArithmeticOverflow)?;
+        plans.push_back((plan, price));
+    }
+    if !authorized {
+        return Err(SynapseError::Unauthorized);
+    }
+    if charge > voucher.total - channel.redeemed {
+        return Err(SynapseError::Underpayment);
+    }
+
+    let mut records = Vec::new(env);
+    for (plan, price) in plans.iter() {
+        let (plan_id, contributor) = (plan.id.clone(), plan.contributor.clone());
+        records.push_back(settle_purchase(env, buyer, plan, token, price, true)?);
+        emit_plan_purchased(env, &plan_id, buyer, price, &contributor, token, &None);
+    }
+
+    channel.redeemed += charge;
+    channel.settled = voucher.plan_ids.len();
+    channel.nonce = voucher.nonce;
+    save_channel(env, buyer, token, &channel);
+    Ok(records)
+}
+
+/// Starts the dispute window on the buyer's channel. Returns the last ledger
+/// on which vouchers can still be redeemed; closing again keeps that ledger.
+pub fn close_channel(env: &Env, buyer: &Address, token: &Address) -> Result<u32, SynapseError> {
+    buyer.require_auth();
+    let mut channel = load_channel(env, buyer, token)?;
+    if let Some(closes_at) = channel.closes_at {
+        return Ok(closes_at);
+    }
+    let closes_at = env.ledger().sequence() + CHANNEL_DISPUTE_WINDOW;
+    channel.closes_at = Some(closes_at);
+    save_channel(env, buyer, token, &channel);
+    // Keep the contract live until the buyer can finalize
+    let ttl = CHANNEL_DISPUTE_WINDOW * 2;
+    env.storage().instance().extend_ttl(ttl, ttl);
+    Ok(closes_at)
+}
+
+/// Once the dispute window has passed, refunds the unredeemed deposit to the
+/// buyer and deletes the channel. Returns the amount refunded.
+pub fn finalize_channel(env: &Env, buyer: &Address, token: &Address) -> Result<i128, SynapseError> {
+    buyer.require_auth();
+    let channel = load_channel(env, buyer, token)?;
+    match channel.closes_at {
+        Some(closes_at) if env.ledger().sequence() > closes_at => {}
+        _ => return Err(SynapseError::DisputeWindowActive),
+    }
+
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Channel(buyer.clone(), token.clone()));
+
+    let refund = channel.deposit - channel.redeemed;
+    if refund > 0 {
+        token::Client::new(env, token).transfer(&env.current_contract_address(), buyer, &refund);
+    }
+    Ok(refund)
+}
@@ -0,0 +1,60 @@
+use soroban_sdk::{Address, Env};
+
+use crate::errors::SynapseError;
+use crate::plan::TTL_HOT;
+use crate::storage_keys::DataKey;
+
+/// A buyer's prepaid balance in `token`, held by the contract.
+pub fn get_balance(env: &Env, buyer: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BuyerBalance(buyer.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+fn set_balance(env: &Env, buyer: &Address, token: &Address, balance: i128) {
+    let key = DataKey::BuyerBalance(buyer.clone(), token.clone());
+    if balance == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &balance);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, TTL_HOT, TTL_HOT);
+    }
+}
+
+/// Adds a deposit to the buyer's balance. Returns the new balance.
+pub fn add_balance(
+    env: &Env,
+    buyer: &Address,
+    token: &Address,
+    amount: i128,
+) -> Result<i128, SynapseError> {
+    if amount <= 0 {
+        return Err(SynapseError::InvalidAmount);
+    }
+    let balance = get_balance(env, buyer, token)
+        .checked_add(amount)
+        .ok_or(SynapseError::ArithmeticOverflow)?;
+    set_balance(env, buyer, token, balance);
+    Ok(balance)
+}
+
+/// Removes `amount` from the buyer's balance. Returns the new balance.
+pub fn sub_balance(
+    env: &Env,
+    buyer: &Address,
+    token: &Address,
+    amount: i128,
+) -> Result<i128, SynapseError> {
+    if amount <= 0 {
+        return Err(SynapseError::InvalidAmount);
+    }
+    let balance = get_balance(env, buyer, token);
+    if balance < amount {
+        return Err(SynapseError::InsufficientBalance);
+    }
+    set_balance(env, buyer, token, balance - amount);
+    Ok(balance - amount)
+}
@@ -0,0 +1,54 @@
+use soroban_sdk::{Address, Env};
+
+use crate::errors::SynapseError;
+use crate::plan::TTL_HOT;
+use crate::storage_keys::DataKey;
+use crate::types::SettlementMode;
+
+/// Defaults to pushing each share straight to its recipient.
+pub fn get_settlement_mode(env: &Env) -> SettlementMode {
+    env.storage()
+        .instance()
+        .get(&DataKey::SettlementMode)
+        .unwrap_or(SettlementMode::Push)
+}
+
+pub fn get_claimable(env: &Env, address: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Claimable(address.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+/// Adds `amount` of `token` to the balance `address` can later claim.
+pub fn credit(
+    env: &Env,
+    address: &Address,
+    token: &Address,
+    amount: i128,
+) -> Result<(), SynapseError> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let balance = get_claimable(env, address, token)
+        .checked_add(amount)
+        .ok_or(SynapseError::ArithmeticOverflow)?;
+    let key = DataKey::Claimable(address.clone(), token.clone());
+    env.storage().persistent().set(&key, &balance);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_HOT, TTL_HOT);
+    Ok(())
+}
+
+/// Zeroes the claimable balance and returns what it held.
+pub fn take_claimable(env: &Env, address: &Address, token: &Address) -> Result<i128, SynapseError> {
+    let balance = get_claimable(env, address, token);
+    if balance <= 0 {
+        return Err(SynapseError::NothingToClaim);
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Claimable(address.clone(), token.clone()));
+    Ok(balance)
+}
@@ -0,0 +1,55 @@
+use soroban_sdk::contracterror;
+
+/// Error codes returned by every fallible contract entrypoint.
+/// Discriminants are part of the public interface — never renumber.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum SynapseError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    InvalidShareBps = 3,
+    ContentExists = 4,
+    PlanNotFound = 5,
+    Unauthorized = 6,
+    InvalidPriceBounds = 7,
+    PriceOutOfBounds = 8,
+    Underpayment = 9,
+    PlanIdExists = 10,
+    NoPendingAdmin = 11,
+    InvalidPlanLayout = 12,
+    Paused = 13,
+    InvalidDelay = 14,
+    ShareChangePending = 15,
+    NoPendingShareChange = 16,
+    TimelockActive = 17,
+    ArithmeticOverflow = 18,
+    InvalidAmount = 19,
+    InvalidBeneficiaries = 20,
+    NothingToClaim = 21,
+    TokenNotAccepted = 22,
+    TokenNotPriced = 23,
+    TokenBoundsNotSet = 24,
+    InsufficientBalance = 25,
+    ChannelExists = 26,
+    ChannelNotFound = 27,
+    InvalidVoucher = 28,
+    ChannelClosed = 29,
+    DisputeWindowActive = 30,
+    AlreadyPurchased = 31,
+    InvalidLicense = 32,
+    SubscriptionNotConfigured = 33,
+    InvalidSubscription = 34,
+    PeriodNotEnded = 35,
+    DuplicatePlan = 36,
+    TooManyResults = 37,
+    SelfReferral = 38,
+    InvalidMetaUpdate = 39,
+    InvalidStatusTransition = 40,
+    PlanNotActive = 41,
+    ContentBlocked = 42,
+    AppealNotAllowed = 43,
+    NoPendingAppeal = 44,
+    AlreadyFlagged = 45,
+    NotPurchased = 46,
+}
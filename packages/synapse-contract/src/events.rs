@@ -1,7 +1,11 @@
 use soroban_sdk::{Address, BytesN, Env, String, Vec};
 
-use crate::types::StorageTier;
+use crate::types::{
+    Channel, PausableOp, PlanMetaUpdate, PlanStatus, PlanVersion, SearchReceipt, ShareChange,
+    StorageTier, SubscriptionPass, SubscriptionProduct, Voucher,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn emit_plan_stored(
     env: &Env,
     plan_id: &BytesN<16>,
@@ -32,6 +36,8 @@ pub fn emit_plan_purchased(
     buyer: &Address,
     amount: i128,
     contributor: &Address,
+    token: &Address,
+    referrer: &Option<Address>,
 ) {
     env.events().publish(
         (soroban_sdk::symbol_short!("plan_pu"),),
@@ -40,10 +46,284 @@ pub fn emit_plan_purchased(
             buyer.clone(),
             amount,
             contributor.clone(),
+            token.clone(),
+            referrer.clone(),
         ),
     );
 }
 
+pub fn emit_price_changed(
+    env: &Env,
+    plan_id: &BytesN<16>,
+    contributor: &Address,
+    token: &Address,
+    old_price: Option<i128>,
+    new_price: i128,
+) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("price_ch"),),
+        (
+            plan_id.clone(),
+            contributor.clone(),
+            token.clone(),
+            old_price,
+            new_price,
+        ),
+    );
+}
+
+pub fn emit_token_added(env: &Env, token: &Address) {
+    env.events()
+        .publish((soroban_sdk::symbol_short!("tok_add"),), token.clone());
+}
+
+pub fn emit_token_removed(env: &Env, token: &Address) {
+    env.events()
+        .publish((soroban_sdk::symbol_short!("tok_rem"),), token.clone());
+}
+
+pub fn emit_admin_proposed(env: &Env, admin: &Address, new_admin: &Address) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("adm_prop"),),
+        (admin.clone(), new_admin.clone()),
+    );
+}
+
+pub fn emit_admin_accepted(env: &Env, old_admin: &Address, new_admin: &Address) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("adm_acc"),),
+        (old_admin.clone(), new_admin.clone()),
+    );
+}
+
+pub fn emit_admin_cancelled(env: &Env, admin: &Address, cancelled: &Address) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("adm_canc"),),
+        (admin.clone(), cancelled.clone()),
+    );
+}
+
+pub fn emit_upgraded(env: &Env, admin: &Address, new_wasm_hash: &BytesN<32>) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("upgraded"),),
+        (admin.clone(), new_wasm_hash.clone()),
+    );
+}
+
+pub fn emit_migrated(env: &Env, from_version: u32, to_version: u32, plans_migrated: u32) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("migrated"),),
+        (from_version, to_version, plans_migrated),
+    );
+}
+
+pub fn emit_paused(env: &Env, caller: &Address, op: &PausableOp) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("paused"),),
+        (caller.clone(), op.clone()),
+    );
+}
+
+pub fn emit_unpaused(env: &Env, caller: &Address, op: &PausableOp) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("unpaused"),),
+        (caller.clone(), op.clone()),
+    );
+}
+
+pub fn emit_share_change_proposed(env: &Env, old_share_bps: u32, change: &ShareChange) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("shr_prop"),),
+        (old_share_bps, change.new_share_bps, change.effective_ledger),
+    );
+}
+
+pub fn emit_share_change_executed(env: &Env, old_share_bps: u32, new_share_bps: u32) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("shr_exec"),),
+        (old_share_bps, new_share_bps),
+    );
+}
+
+pub fn emit_share_change_cancelled(env: &Env, change: &ShareChange) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("shr_canc"),),
+        (change.new_share_bps, change.effective_ledger),
+    );
+}
+
+pub fn emit_claimed(env: &Env, address: &Address, token: &Address, amount: i128) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("claimed"),),
+        (address.clone(), token.clone(), amount),
+    );
+}
+
+pub fn emit_deposited(env: &Env, buyer: &Address, token: &Address, amount: i128, balance: i128) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("deposit"),),
+        (buyer.clone(), token.clone(), amount, balance),
+    );
+}
+
+pub fn emit_withdrawn(env: &Env, buyer: &Address, token: &Address, amount: i128, balance: i128) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("withdraw"),),
+        (buyer.clone(), token.clone(), amount, balance),
+    );
+}
+
+pub fn emit_balance_debited(
+    env: &Env,
+    buyer: &Address,
+    plan_id: &BytesN<16>,
+    token: &Address,
+    amount: i128,
+    balance: i128,
+) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("debit"),),
+        (
+            buyer.clone(),
+            plan_id.clone(),
+            token.clone(),
+            amount,
+            balance,
+        ),
+    );
+}
+
+pub fn emit_channel_opened(env: &Env, buyer: &Address, token: &Address, channel: &Channel) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("ch_open"),),
+        (buyer.clone(), token.clone(), channel.id, channel.deposit),
+    );
+}
+
+pub fn emit_voucher_redeemed(env: &Env, redeemer: &Address, voucher: &Voucher, count: u32) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("redeemed"),),
+        (
+            redeemer.clone(),
+            voucher.buyer.clone(),
+            voucher.channel_id,
+            voucher.nonce,
+            count,
+        ),
+    );
+}
+
+pub fn emit_channel_closing(env: &Env, buyer: &Address, token: &Address, closes_at: u32) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("ch_close"),),
+        (buyer.clone(), token.clone(), closes_at),
+    );
+}
+
+pub fn emit_channel_finalized(env: &Env, buyer: &Address, token: &Address, refund: i128) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("ch_final"),),
+        (buyer.clone(), token.clone(), refund),
+    );
+}
+
+pub fn emit_subscription_set(env: &Env, product: &Option<SubscriptionProduct>) {
+    env.events().publish((soroban_sdk::symbol_short!("sub_set"),), product.clone());
+}
+
+pub fn emit_subscription_bought(env: &Env, buyer: &Address, pass: &SubscriptionPass) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("sub_buy"),),
+        (buyer.clone(), pass.token.clone(), pass.expires_at_ledger),
+    );
+}
+
+pub fn emit_pass_recall(env: &Env, buyer: &Address, plan_id: &BytesN<16>, contributor: &Address) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("recalled"),),
+        (buyer.clone(), plan_id.clone(), contributor.clone()),
+    );
+}
+
+pub fn emit_subscription_distributed(env: &Env, period: u32, token: &Address, amount: i128) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("sub_dist"),),
+        (period, token.clone(), amount),
+    );
+}
+
+pub fn emit_search_paid(env: &Env, receipt: &SearchReceipt) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("search"),),
+        (
+            receipt.id,
+            receipt.buyer.clone(),
+            receipt.query_hash.clone(),
+            receipt.fee,
+            receipt.contributor_share,
+        ),
+    );
+}
+
+pub fn emit_plan_versioned(env: &Env, plan_id: &BytesN<16>, version: &PlanVersion) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("plan_ver"),),
+        (
+            plan_id.clone(),
+            version.version,
+            version.content_hash.clone(),
+            version.ipfs_cid.clone(),
+        ),
+    );
+}
+
+pub fn emit_plan_updated(env: &Env, plan_id: &BytesN<16>, update: &PlanMetaUpdate) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("plan_up"),),
+        (plan_id.clone(), update.clone()),
+    );
+}
+
+pub fn emit_status_changed(
+    env: &Env,
+    plan_id: &BytesN<16>,
+    old_status: &PlanStatus,
+    new_status: &PlanStatus,
+) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("plan_sts"),),
+        (plan_id.clone(), old_status.clone(), new_status.clone()),
+    );
+}
+
+pub fn emit_takedown(env: &Env, plan_id: &BytesN<16>, moderator: &Address, reason: u32) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("takedown"),),
+        (plan_id.clone(), moderator.clone(), reason),
+    );
+}
+
+pub fn emit_appeal_filed(env: &Env, plan_id: &BytesN<16>, statement_hash: &BytesN<32>) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("appeal"),),
+        (plan_id.clone(), statement_hash.clone()),
+    );
+}
+
+pub fn emit_appeal_resolved(env: &Env, plan_id: &BytesN<16>, upheld: bool) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("appeal_rs"),),
+        (plan_id.clone(), upheld),
+    );
+}
+
+pub fn emit_flagged(env: &Env, plan_id: &BytesN<16>, buyer: &Address, reason: u32, count: u32) {
+    env.events().publish(
+        (soroban_sdk::symbol_short!("flagged"),),
+        (plan_id.clone(), buyer.clone(), reason, count),
+    );
+}
+
 pub fn emit_tier_changed(
     env: &Env,
     plan_id: &BytesN<16>,
@@ -0,0 +1,85 @@
+use soroban_sdk::Env;
+
+use crate::errors::SynapseError;
+use crate::split::MAX_BPS;
+use crate::storage_keys::DataKey;
+use crate::types::ShareChange;
+
+/// Ledgers between proposing and executing a share change (~7 days at ~5s/ledger)
+const DEFAULT_SHARE_CHANGE_DELAY: u32 = 120_960;
+/// Shortest delay the admin may configure (~1 day)
+pub const MIN_SHARE_CHANGE_DELAY: u32 = 17_280;
+/// How long a proposal stays live once executable (~1 day)
+const EXECUTION_WINDOW: u32 = 17_280;
+
+pub fn get_share_change_delay(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ShareChangeDelay)
+        .unwrap_or(DEFAULT_SHARE_CHANGE_DELAY)
+}
+
+pub fn set_share_change_delay(env: &Env, delay_ledgers: u32) -> Result<(), SynapseError> {
+    if delay_ledgers < MIN_SHARE_CHANGE_DELAY {
+        return Err(SynapseError::InvalidDelay);
+    }
+    env.storage()
+        .instance()
+        .set(&DataKey::ShareChangeDelay, &delay_ledgers);
+    Ok(())
+}
+
+pub fn get_pending_share_change(env: &Env) -> Option<ShareChange> {
+    env.storage().instance().get(&DataKey::PendingShareChange)
+}
+
+/// Records a proposal that becomes executable after the configured delay.
+/// Only one proposal may be pending at a time.
+pub fn propose_share_change(env: &Env, new_share_bps: u32) -> Result<ShareChange, SynapseError> {
+    if new_share_bps > MAX_BPS {
+        return Err(SynapseError::InvalidShareBps);
+    }
+    if get_pending_share_change(env).is_some() {
+        return Err(SynapseError::ShareChangePending);
+    }
+
+    let delay = get_share_change_delay(env);
+    let proposed_at = env.ledger().sequence();
+    let change = ShareChange {
+        new_share_bps,
+        proposed_at_ledger: proposed_at,
+        effective_ledger: proposed_at.saturating_add(delay),
+    };
+    env.storage()
+        .instance()
+        .set(&DataKey::PendingShareChange, &change);
+
+    // Keep the proposal from being archived before it can be executed
+    let ttl = delay.saturating_add(EXECUTION_WINDOW);
+    env.storage().instance().extend_ttl(ttl, ttl);
+    Ok(change)
+}
+
+/// Applies the pending proposal once its effective ledger has been reached.
+pub fn execute_share_change(env: &Env) -> Result<ShareChange, SynapseError> {
+    let change = get_pending_share_change(env).ok_or(SynapseError::NoPendingShareChange)?;
+    if env.ledger().sequence() < change.effective_ledger {
+        return Err(SynapseError::TimelockActive);
+    }
+
+    env.storage()
+        .instance()
+        .set(&DataKey::ContributorShareBps, &change.new_share_bps);
+    env.storage()
+        .instance()
+        .remove(&DataKey::PendingShareChange);
+    Ok(change)
+}
+
+pub fn cancel_share_change(env: &Env) -> Result<ShareChange, SynapseError> {
+    let change = get_pending_share_change(env).ok_or(SynapseError::NoPendingShareChange)?;
+    env.storage()
+        .instance()
+        .remove(&DataKey::PendingShareChange);
+    Ok(change)
+}
@@ -1,41 +1,76 @@
 #![no_std]
 
+mod access;
 mod admin;
+mod channel;
+mod credits;
+mod earnings;
+mod errors;
 mod events;
+mod governance;
+mod migration;
+mod moderation;
+mod pause;
 mod plan;
 mod purchase;
+mod search;
+mod split;
 mod storage_keys;
+mod subscription;
+mod tokens;
 mod types;
 
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
+use soroban_sdk::{contract, contractimpl, token, Address, BytesN, Env, String, Vec};
 
-use crate::admin::{get_contributor_share_pct, require_admin};
-use crate::events::{emit_plan_purchased, emit_plan_stored, emit_tier_changed};
+use crate::admin::{
+    get_admin, get_contributor_share_bps, get_dust_recipient, get_native_token, get_pending_admin,
+    get_price_bounds, get_referral_share_bps, get_royalty_bps, require_admin, set_pending_admin,
+    take_pending_admin,
+};
+use crate::errors::SynapseError;
+use crate::events::{
+    emit_admin_accepted, emit_admin_cancelled, emit_admin_proposed, emit_appeal_filed,
+    emit_appeal_resolved, emit_channel_closing, emit_channel_finalized, emit_channel_opened,
+    emit_claimed, emit_deposited, emit_flagged, emit_migrated, emit_pass_recall, emit_paused,
+    emit_plan_purchased, emit_plan_stored, emit_plan_updated, emit_plan_versioned,
+    emit_price_changed, emit_search_paid, emit_share_change_cancelled, emit_share_change_executed,
+    emit_share_change_proposed, emit_status_changed, emit_subscription_bought,
+    emit_subscription_distributed, emit_subscription_set, emit_takedown, emit_tier_changed,
+    emit_token_added, emit_token_removed, emit_unpaused, emit_upgraded, emit_voucher_redeemed,
+    emit_withdrawn,
+};
+use crate::migration::SCHEMA_VERSION;
 use crate::plan as plan_mod;
 use crate::purchase as purchase_mod;
-use crate::storage_keys::DataKey;
-use crate::types::{KBStatsResult, PlanMeta, PurchaseRecord, StorageTier, StorePlanInput};
+use crate::split::MAX_BPS;
+use crate::storage_keys::{DataKey, DataKeyExt};
+use crate::types::{
+    Beneficiary, Channel, DustRecipient, Entitlement, KBStatsResult, LicenseModel, PausableOp,
+    PlanFlag, PlanMeta, PlanMetaUpdate, PlanStatus, PlanVersion, PurchaseRecord, SearchReceipt,
+    SettlementMode, ShareChange, StorageTier, StorePlanInput, SubscriptionPass,
+    SubscriptionProduct, Takedown, Voucher,
+};
 
 #[contract]
 pub struct SynapseContract;
 
 #[contractimpl]
 impl SynapseContract {
-    /// One-time initialization of the contract.
+    /// One-time initialization of the contract. The contributor share is in
+    /// basis points (7000 = 70%).
     pub fn initialize(
         env: Env,
         admin: Address,
         operator: Address,
-        contributor_share_pct: u32,
+        contributor_share_bps: u32,
         native_token: Address,
-    ) {
+    ) -> Result<(), SynapseError> {
         if env.storage().instance().has(&DataKey::Admin) {
-            panic!("Already initialized");
+            return Err(SynapseError::AlreadyInitialized);
+        }
+        if contributor_share_bps > MAX_BPS {
+            return Err(SynapseError::InvalidShareBps);
         }
-        assert!(
-            contributor_share_pct <= 100,
-            "Share percentage must be <= 100"
-        );
 
         env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage()
@@ -43,33 +78,56 @@ impl SynapseContract {
             .set(&DataKey::OperatorAddress, &operator);
         env.storage()
             .instance()
-            .set(&DataKey::ContributorSharePct, &contributor_share_pct);
+            .set(&DataKey::ContributorShareBps, &contributor_share_bps);
         env.storage()
             .instance()
             .set(&DataKey::NativeTokenAddress, &native_token);
+        tokens::set_accepted_tokens(&env, &Vec::from_array(&env, [native_token.clone()]));
         env.storage().instance().set(&DataKey::PlanCount, &0u32);
         env.storage()
             .instance()
             .set(&DataKey::TotalPurchases, &0u32);
+        migration::set_schema_version(&env, SCHEMA_VERSION);
+
+        Ok(())
     }
 
     /// Store a new plan's metadata on-chain. Content lives on IPFS.
     /// Uses StorePlanInput struct to stay under the 10-param Soroban limit.
+    /// Returns the plan ID, which is derived from the content hash and
+    /// contributor when `input.id` is `None`.
     pub fn store_plan(
         env: Env,
         contributor: Address,
         input: StorePlanInput,
-    ) {
+    ) -> Result<BytesN<16>, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::StorePlan)?;
         contributor.require_auth();
 
+        moderation::require_not_blocked(&env, &input.content_hash)?;
         // Dedup check
         if plan_mod::content_exists(&env, &input.content_hash) {
-            panic!("Content with this hash already exists");
+            return Err(SynapseError::ContentExists);
+        }
+        plan_mod::validate_price(&env, input.price)?;
+        plan_mod::validate_beneficiaries(&input.beneficiaries)?;
+        plan_mod::validate_license(&input.license)?;
+        if let Some(parent) = &input.parent {
+            plan_mod::load_plan(&env, parent)?;
+        }
+
+        let plan_id = match input.id {
+            Some(id) => id,
+            None => plan_mod::derive_plan_id(&env, &input.content_hash, &contributor),
+        };
+        // Never overwrite another plan's metadata
+        if plan_mod::plan_exists(&env, &plan_id) {
+            return Err(SynapseError::PlanIdExists);
         }
 
         let tier = StorageTier::Hot;
         let plan = PlanMeta {
-            id: input.id.clone(),
+            id: plan_id.clone(),
             title: input.title.clone(),
             description: input.description,
             content_hash: input.content_hash.clone(),
@@ -80,16 +138,25 @@ impl SynapseContract {
             framework: input.framework,
             contributor: contributor.clone(),
             quality_score: input.quality_score,
+            price: input.price,
             purchase_count: 0,
             tier: tier.clone(),
             created_at: env.ledger().timestamp(),
         };
 
         plan_mod::save_plan(&env, &plan);
+        plan_mod::save_beneficiaries(&env, &plan, &input.beneficiaries);
+        plan_mod::save_license(&env, &plan_id, &input.license);
+        if let Some(parent) = &input.parent {
+            plan_mod::save_parent(&env, &plan_id, parent);
+        }
+        if plan_mod::get_plan_review(&env) {
+            plan_mod::set_status(&env, &plan_id, &PlanStatus::Pending);
+        }
 
         emit_plan_stored(
             &env,
-            &input.id,
+            &plan_id,
             &input.content_hash,
             &contributor,
             &input.title,
@@ -97,6 +164,13 @@ impl SynapseContract {
             &input.ipfs_cid,
             &tier,
         );
+
+        Ok(plan_id)
+    }
+
+    /// Compute the ID `store_plan` would derive for this content and contributor.
+    pub fn derive_plan_id(env: Env, contributor: Address, content_hash: BytesN<32>) -> BytesN<16> {
+        plan_mod::derive_plan_id(&env, &content_hash, &contributor)
     }
 
     /// Read plan metadata by ID.
@@ -109,20 +183,148 @@ impl SynapseContract {
         plan_mod::content_exists(&env, &content_hash)
     }
 
-    /// Purchase a plan: atomic 70/30 XLM split between contributor and operator.
+    /// Purchase a plan at its listed price in `token`: atomic split between
+    /// contributor and operator. `amount` is the most the buyer will pay.
+    /// An optional `referrer` receives the referral share of the operator's
+    /// cut; buyers cannot refer themselves.
     pub fn purchase_plan(
         env: Env,
         buyer: Address,
         plan_id: BytesN<16>,
+        token: Address,
         amount: i128,
-    ) -> PurchaseRecord {
-        let plan = plan_mod::get_plan(&env, &plan_id).expect("Plan not found");
+        referrer: Option<Address>,
+    ) -> Result<PurchaseRecord, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::PurchasePlan)?;
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
+
+        // Pass holders are not charged; the recall counts toward the pool
+        if let Some(pass) = subscription::get_active_pass(&env, &buyer) {
+            buyer.require_auth();
+            let record = subscription::record_recall(&env, &buyer, &plan, &pass)?;
+            emit_pass_recall(&env, &buyer, &plan_id, &plan.contributor);
+            return Ok(record);
+        }
+
+        let record =
+            purchase_mod::execute_purchase(&env, &buyer, &plan_id, &token, amount, &referrer)?;
 
-        let record = purchase_mod::execute_purchase(&env, &buyer, &plan_id, amount);
+        emit_plan_purchased(
+            &env,
+            &plan_id,
+            &buyer,
+            record.amount_stroops,
+            &plan.contributor,
+            &token,
+            &referrer,
+        );
 
-        emit_plan_purchased(&env, &plan_id, &buyer, amount, &plan.contributor);
+        Ok(record)
+    }
+
+    /// Check whether a buyer currently has access to a plan. Time-limited
+    /// licenses lapse at their `expires_at_ledger`.
+    pub fn has_access(env: Env, buyer: Address, plan_id: BytesN<16>) -> bool {
+        access::has_access(&env, &plan_id, &buyer)
+    }
 
-        record
+    /// Get a buyer's entitlement to a plan, including lapsed ones.
+    pub fn get_entitlement(env: Env, buyer: Address, plan_id: BytesN<16>) -> Option<Entitlement> {
+        access::get_entitlement(&env, &plan_id, &buyer)
+    }
+
+    /// Get the plan a derivative extends, if any.
+    pub fn get_parent(env: Env, plan_id: BytesN<16>) -> Option<BytesN<16>> {
+        plan_mod::get_parent(&env, &plan_id)
+    }
+
+    /// Get the plans that directly extend this one.
+    pub fn get_derivatives(env: Env, plan_id: BytesN<16>) -> Vec<BytesN<16>> {
+        plan_mod::get_derivatives(&env, &plan_id)
+    }
+
+    /// Get how long a purchase of the plan grants access.
+    pub fn get_license(env: Env, plan_id: BytesN<16>) -> LicenseModel {
+        plan_mod::get_license(&env, &plan_id)
+    }
+
+    /// Buy several plans in one call, paying in `token`. Fails as a whole if
+    /// the list is empty, any plan cannot be bought, or the prices sum to
+    /// more than `max_total`.
+    /// Each recipient receives a single transfer for all of their shares.
+    pub fn purchase_plans(
+        env: Env,
+        buyer: Address,
+        plan_ids: Vec<BytesN<16>>,
+        token: Address,
+        max_total: i128,
+    ) -> Result<Vec<PurchaseRecord>, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::PurchasePlan)?;
+        if plan_ids.is_empty() {
+            return Err(SynapseError::InvalidAmount);
+        }
+
+        if let Some(pass) = subscription::get_active_pass(&env, &buyer) {
+            buyer.require_auth();
+            let mut records = Vec::new(&env);
+            for plan_id in plan_ids.iter() {
+                let plan = plan_mod::load_plan(&env, &plan_id)?;
+                records.push_back(subscription::record_recall(&env, &buyer, &plan, &pass)?);
+                emit_pass_recall(&env, &buyer, &plan_id, &plan.contributor);
+            }
+            return Ok(records);
+        }
+
+        let records =
+            purchase_mod::execute_batch(&env, &buyer, &plan_ids, &token, max_total, &None)?;
+        for (plan_id, record) in plan_ids.iter().zip(records.iter()) {
+            let contributor = plan_mod::load_plan(&env, &plan_id)?.contributor;
+            emit_plan_purchased(
+                &env,
+                &plan_id,
+                &buyer,
+                record.amount_stroops,
+                &contributor,
+                &token,
+                &None,
+            );
+        }
+        Ok(records)
+    }
+
+    /// Pay the search fee for a query and record a receipt. `query_hash`
+    /// identifies the query; `result_plan_ids` are the plans returned, whose
+    /// contributors share the configured slice of the fee.
+    pub fn pay_search(
+        env: Env,
+        buyer: Address,
+        query_hash: BytesN<32>,
+        result_plan_ids: Vec<BytesN<16>>,
+    ) -> Result<SearchReceipt, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::PaySearch)?;
+        let receipt = search::pay_search(&env, &buyer, &query_hash, &result_plan_ids)?;
+        emit_search_paid(&env, &receipt);
+        Ok(receipt)
+    }
+
+    /// Get a search receipt by ID.
+    pub fn get_search_receipt(env: Env, id: u64) -> Option<SearchReceipt> {
+        search::get_receipt(&env, id)
+    }
+
+    /// Get the (fee, contributor share in bps) charged per search.
+    pub fn get_search_fee(env: Env) -> (i128, u32) {
+        (
+            search::get_search_fee(&env),
+            search::get_search_share_bps(&env),
+        )
+    }
+
+    /// Admin-only: set the search fee in stroops and the slice of it, in
+    /// basis points, shared with the contributors of returned plans.
+    pub fn set_search_fee(env: Env, fee: i128, share_bps: u32) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        search::set_search_fee(&env, fee, share_bps)
     }
 
     /// Get purchase history for a plan.
@@ -130,21 +332,315 @@ impl SynapseContract {
         purchase_mod::get_purchases(&env, &plan_id)
     }
 
-    /// Get all plan IDs contributed by an address.
+    /// Get who shares a plan's contributor share, with their weights.
+    pub fn get_beneficiaries(
+        env: Env,
+        plan_id: BytesN<16>,
+    ) -> Result<Vec<Beneficiary>, SynapseError> {
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
+        Ok(plan_mod::get_beneficiaries(&env, &plan))
+    }
+
+    /// Get all plan IDs contributed or co-authored by an address.
     pub fn get_contributor_plans(env: Env, contributor: Address) -> Vec<BytesN<16>> {
         plan_mod::get_contributor_plans(&env, &contributor)
     }
 
+    /// Publish revised content for a plan (contributor only). The plan keeps
+    /// its ID, price and purchase history; the previous content remains in
+    /// its version log. Returns the new version.
+    pub fn update_plan_content(
+        env: Env,
+        contributor: Address,
+        plan_id: BytesN<16>,
+        content_hash: BytesN<32>,
+        ipfs_cid: String,
+    ) -> Result<PlanVersion, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::StorePlan)?;
+        contributor.require_auth();
+
+        let mut plan = plan_mod::load_plan(&env, &plan_id)?;
+        if contributor != plan.contributor {
+            return Err(SynapseError::Unauthorized);
+        }
+        if moderation::get_takedown(&env, &plan_id).is_some() {
+            return Err(SynapseError::PlanNotActive);
+        }
+        moderation::require_not_blocked(&env, &content_hash)?;
+        let version = plan_mod::add_version(&env, &mut plan, &content_hash, &ipfs_cid)?;
+
+        emit_plan_versioned(&env, &plan_id, &version);
+        Ok(version)
+    }
+
+    /// Get version `n` of a plan's content, counting from 1.
+    pub fn get_plan_version(env: Env, plan_id: BytesN<16>, n: u32) -> Option<PlanVersion> {
+        let plan = plan_mod::get_plan(&env, &plan_id)?;
+        plan_mod::get_versions(&env, &plan).get(n.checked_sub(1)?)
+    }
+
+    /// Get how many content versions a plan has.
+    pub fn get_version_count(env: Env, plan_id: BytesN<16>) -> Result<u32, SynapseError> {
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
+        Ok(plan_mod::get_versions(&env, &plan).len())
+    }
+
+    /// Get whether a plan is listed for sale.
+    pub fn get_plan_status(env: Env, plan_id: BytesN<16>) -> Result<PlanStatus, SynapseError> {
+        plan_mod::load_plan(&env, &plan_id)?;
+        Ok(plan_mod::get_status(&env, &plan_id))
+    }
+
+    /// Contributor only: stop selling an active plan, optionally pointing
+    /// buyers at an active replacement. Calling again updates the pointer.
+    pub fn deprecate_plan(
+        env: Env,
+        contributor: Address,
+        plan_id: BytesN<16>,
+        replacement: Option<BytesN<16>>,
+    ) -> Result<(), SynapseError> {
+        contributor.require_auth();
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
+        if contributor != plan.contributor {
+            return Err(SynapseError::Unauthorized);
+        }
+        if let Some(replacement) = &replacement {
+            if *replacement == plan_id {
+                return Err(SynapseError::InvalidStatusTransition);
+            }
+            plan_mod::load_plan(&env, replacement)?;
+            plan_mod::require_active(&env, replacement)?;
+        }
+
+        let old_status = plan_mod::get_status(&env, &plan_id);
+        match old_status {
+            PlanStatus::Active | PlanStatus::Deprecated(_) => {}
+            _ => return Err(SynapseError::InvalidStatusTransition),
+        }
+        let new_status = PlanStatus::Deprecated(replacement);
+        plan_mod::set_status(&env, &plan_id, &new_status);
+        emit_status_changed(&env, &plan_id, &old_status, &new_status);
+        Ok(())
+    }
+
+    /// Admin-only: delist a plan for policy reasons. `reason` is an
+    /// off-chain policy code.
+    pub fn remove_plan(env: Env, plan_id: BytesN<16>, reason: u32) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        plan_mod::load_plan(&env, &plan_id)?;
+
+        let old_status = plan_mod::get_status(&env, &plan_id);
+        if let PlanStatus::Removed(_) = old_status {
+            return Err(SynapseError::InvalidStatusTransition);
+        }
+        let new_status = PlanStatus::Removed(reason);
+        plan_mod::set_status(&env, &plan_id, &new_status);
+        emit_status_changed(&env, &plan_id, &old_status, &new_status);
+        Ok(())
+    }
+
+    /// Admin-only: list a pending plan, release a quarantined one, or
+    /// reinstate a removed one. Plans taken down by moderation can only
+    /// return through `resolve_appeal`.
+    pub fn activate_plan(env: Env, plan_id: BytesN<16>) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        plan_mod::load_plan(&env, &plan_id)?;
+        if moderation::get_takedown(&env, &plan_id).is_some() {
+            return Err(SynapseError::InvalidStatusTransition);
+        }
+
+        let old_status = plan_mod::get_status(&env, &plan_id);
+        match old_status {
+            PlanStatus::Pending | PlanStatus::Removed(_) | PlanStatus::Quarantined => {}
+            _ => return Err(SynapseError::InvalidStatusTransition),
+        }
+        plan_mod::set_status(&env, &plan_id, &PlanStatus::Active);
+        moderation::mark_flags_reviewed(&env, &plan_id);
+        emit_status_changed(&env, &plan_id, &old_status, &PlanStatus::Active);
+        Ok(())
+    }
+
+    /// Admin or moderator: take down an illegal or malicious plan. The plan
+    /// is delisted and every content hash it has used is blocklisted, so the
+    /// content cannot be stored again. `reason` is an off-chain policy code.
+    pub fn takedown(
+        env: Env,
+        caller: Address,
+        plan_id: BytesN<16>,
+        reason: u32,
+    ) -> Result<(), SynapseError> {
+        moderation::require_moderator(&env, &caller)?;
+        let old_status = moderation::takedown(&env, &caller, &plan_id, reason)?;
+
+        emit_takedown(&env, &plan_id, &caller, reason);
+        emit_status_changed(&env, &plan_id, &old_status, &PlanStatus::Removed(reason));
+        Ok(())
+    }
+
+    /// Contributor only: appeal a takedown once. `statement_hash` identifies
+    /// the contributor's statement, kept off-chain.
+    pub fn appeal(
+        env: Env,
+        contributor: Address,
+        plan_id: BytesN<16>,
+        statement_hash: BytesN<32>,
+    ) -> Result<(), SynapseError> {
+        contributor.require_auth();
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
+        if contributor != plan.contributor {
+            return Err(SynapseError::Unauthorized);
+        }
+        moderation::file_appeal(&env, &plan_id, &statement_hash)?;
+        emit_appeal_filed(&env, &plan_id, &statement_hash);
+        Ok(())
+    }
+
+    /// Admin-only: decide a pending appeal. Upholding it restores the plan's
+    /// status from before the takedown and lifts its blocklist entries;
+    /// rejecting it is final.
+    pub fn resolve_appeal(env: Env, plan_id: BytesN<16>, upheld: bool) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        let old_status = plan_mod::get_status(&env, &plan_id);
+        let restored = moderation::resolve_appeal(&env, &plan_id, upheld)?;
+
+        emit_appeal_resolved(&env, &plan_id, upheld);
+        if let Some(new_status) = restored {
+            emit_status_changed(&env, &plan_id, &old_status, &new_status);
+        }
+        Ok(())
+    }
+
+    /// Buyer only: flag a plan you bought, once, with an off-chain reason
+    /// code. Reaching the flag threshold quarantines the plan, halting sales
+    /// until the admin releases or removes it; flags filed before a release
+    /// do not count again. Returns the plan's flag count.
+    pub fn flag_plan(
+        env: Env,
+        buyer: Address,
+        plan_id: BytesN<16>,
+        reason: u32,
+    ) -> Result<u32, SynapseError> {
+        buyer.require_auth();
+        let (count, quarantined_from) = moderation::flag(&env, &buyer, &plan_id, reason)?;
+
+        emit_flagged(&env, &plan_id, &buyer, reason, count);
+        if let Some(old_status) = quarantined_from {
+            emit_status_changed(&env, &plan_id, &old_status, &PlanStatus::Quarantined);
+        }
+        Ok(count)
+    }
+
+    /// Get the flags filed against a plan, oldest first.
+    pub fn get_flags(env: Env, plan_id: BytesN<16>) -> Vec<PlanFlag> {
+        moderation::get_flags(&env, &plan_id)
+    }
+
+    /// Get the number of buyers who have flagged a plan.
+    pub fn get_flag_count(env: Env, plan_id: BytesN<16>) -> u32 {
+        moderation::get_flags(&env, &plan_id).len()
+    }
+
+    /// Get the flag count that quarantines a plan; zero means never.
+    pub fn get_flag_threshold(env: Env) -> u32 {
+        moderation::get_flag_threshold(&env)
+    }
+
+    /// Admin-only: set the flag count that quarantines a plan. Zero turns
+    /// automatic quarantine off.
+    pub fn set_flag_threshold(env: Env, threshold: u32) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::FlagThreshold, &threshold);
+        Ok(())
+    }
+
+    /// Get a plan's takedown record, if it has been taken down.
+    pub fn get_takedown(env: Env, plan_id: BytesN<16>) -> Option<Takedown> {
+        moderation::get_takedown(&env, &plan_id)
+    }
+
+    /// Check whether a content hash is blocklisted.
+    pub fn is_content_blocked(env: Env, content_hash: BytesN<32>) -> bool {
+        moderation::is_blocked(&env, &content_hash)
+    }
+
+    /// Admin-only: designate (or clear) a moderator who may take down plans.
+    pub fn set_moderator(env: Env, moderator: Option<Address>) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        match moderator {
+            Some(moderator) => env
+                .storage()
+                .instance()
+                .set(&DataKey::Moderator, &moderator),
+            None => env.storage().instance().remove(&DataKey::Moderator),
+        }
+        Ok(())
+    }
+
+    /// Get the current moderator, if any.
+    pub fn get_moderator(env: Env) -> Option<Address> {
+        moderation::get_moderator(&env)
+    }
+
+    /// Get whether new plans await admin approval.
+    pub fn get_plan_review(env: Env) -> bool {
+        plan_mod::get_plan_review(&env)
+    }
+
+    /// Admin-only: require (or stop requiring) approval of new plans.
+    pub fn set_plan_review(env: Env, required: bool) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKeyExt::PlanReview, &required);
+        Ok(())
+    }
+
+    /// Edit a plan's descriptive metadata (admin or contributor only). Only
+    /// the fields set in `update` change.
+    pub fn update_plan_meta(
+        env: Env,
+        caller: Address,
+        plan_id: BytesN<16>,
+        update: PlanMetaUpdate,
+    ) -> Result<(), SynapseError> {
+        pause::require_not_paused(&env, PausableOp::StorePlan)?;
+        caller.require_auth();
+
+        let mut plan = plan_mod::load_plan(&env, &plan_id)?;
+
+        // Only admin or the plan's contributor can edit metadata
+        let admin = get_admin(&env)?;
+        if caller != admin && caller != plan.contributor {
+            return Err(SynapseError::Unauthorized);
+        }
+
+        plan_mod::validate_meta_update(&update)?;
+        plan_mod::apply_meta_update(&mut plan, &update);
+        plan_mod::update_plan(&env, &plan);
+
+        emit_plan_updated(&env, &plan_id, &update);
+
+        Ok(())
+    }
+
     /// Change storage tier (admin or contributor only).
-    pub fn set_tier(env: Env, caller: Address, plan_id: BytesN<16>, new_tier: StorageTier) {
+    pub fn set_tier(
+        env: Env,
+        caller: Address,
+        plan_id: BytesN<16>,
+        new_tier: StorageTier,
+    ) -> Result<(), SynapseError> {
+        pause::require_not_paused(&env, PausableOp::SetTier)?;
         caller.require_auth();
 
-        let mut plan = plan_mod::get_plan(&env, &plan_id).expect("Plan not found");
+        let mut plan = plan_mod::load_plan(&env, &plan_id)?;
 
         // Only admin or the plan's contributor can change tier
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        let admin = get_admin(&env)?;
         if caller != admin && caller != plan.contributor {
-            panic!("Only admin or contributor can change tier");
+            return Err(SynapseError::Unauthorized);
         }
 
         let old_tier = plan.tier.clone();
@@ -153,12 +649,89 @@ impl SynapseContract {
         plan_mod::bump_ttl(&env, &plan_id, &new_tier);
 
         emit_tier_changed(&env, &plan_id, &old_tier, &new_tier);
+
+        Ok(())
+    }
+
+    /// Set a plan's price in an accepted token (contributor only). The price
+    /// must be within the admin bounds for that token; see
+    /// `get_token_price_bounds`.
+    pub fn set_price(
+        env: Env,
+        contributor: Address,
+        plan_id: BytesN<16>,
+        token: Address,
+        new_price: i128,
+    ) -> Result<(), SynapseError> {
+        contributor.require_auth();
+
+        let mut plan = plan_mod::load_plan(&env, &plan_id)?;
+        if contributor != plan.contributor {
+            return Err(SynapseError::Unauthorized);
+        }
+        tokens::require_accepted(&env, &token)?;
+
+        let old_price = tokens::get_plan_price(&env, &plan, &token).ok();
+        if token == get_native_token(&env)? {
+            plan_mod::validate_price(&env, new_price)?;
+            plan.price = new_price;
+            plan_mod::update_plan(&env, &plan);
+        } else {
+            tokens::validate_price(&env, &token, new_price)?;
+            tokens::set_plan_price(&env, &plan_id, &token, new_price);
+        }
+
+        emit_price_changed(&env, &plan_id, &contributor, &token, old_price, new_price);
+
+        Ok(())
+    }
+
+    /// Get a plan's price in `token`.
+    pub fn get_plan_price(
+        env: Env,
+        plan_id: BytesN<16>,
+        token: Address,
+    ) -> Result<i128, SynapseError> {
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
+        tokens::get_plan_price(&env, &plan, &token)
+    }
+
+    /// Get the token contracts purchases may be paid in.
+    pub fn get_accepted_tokens(env: Env) -> Result<Vec<Address>, SynapseError> {
+        tokens::get_accepted_tokens(&env)
+    }
+
+    /// Admin-only: accept payments in another token contract.
+    pub fn add_token(env: Env, token: Address) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        let mut accepted = tokens::get_accepted_tokens(&env)?;
+        if !accepted.contains(&token) {
+            accepted.push_back(token.clone());
+            tokens::set_accepted_tokens(&env, &accepted);
+            emit_token_added(&env, &token);
+        }
+        Ok(())
+    }
+
+    /// Admin-only: stop accepting a token. Claimable balances in it can
+    /// still be claimed.
+    pub fn remove_token(env: Env, token: Address) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        let mut accepted = tokens::get_accepted_tokens(&env)?;
+        let index = accepted
+            .first_index_of(&token)
+            .ok_or(SynapseError::TokenNotAccepted)?;
+        accepted.remove(index);
+        tokens::set_accepted_tokens(&env, &accepted);
+        emit_token_removed(&env, &token);
+        Ok(())
     }
 
     /// Extend TTL for a plan based on its current tier.
-    pub fn bump_plan_ttl(env: Env, plan_id: BytesN<16>) {
-        let plan = plan_mod::get_plan(&env, &plan_id).expect("Plan not found");
+    pub fn bump_plan_ttl(env: Env, plan_id: BytesN<16>) -> Result<(), SynapseError> {
+        let plan = plan_mod::load_plan(&env, &plan_id)?;
         plan_mod::bump_ttl(&env, &plan_id, &plan.tier);
+        Ok(())
     }
 
     /// Get global stats: total plans and total purchases.
@@ -179,17 +752,462 @@ impl SynapseContract {
         }
     }
 
-    /// Get the contributor share percentage.
-    pub fn get_share_pct(env: Env) -> u32 {
-        get_contributor_share_pct(&env)
+    /// Get the contributor share in basis points.
+    pub fn get_share_bps(env: Env) -> Result<u32, SynapseError> {
+        get_contributor_share_bps(&env)
+    }
+
+    /// Get which party receives the rounding remainder of each split.
+    pub fn get_dust_recipient(env: Env) -> DustRecipient {
+        get_dust_recipient(&env)
+    }
+
+    /// Admin-only: choose which party receives the rounding remainder.
+    pub fn set_dust_recipient(env: Env, recipient: DustRecipient) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::DustRecipient, &recipient);
+        Ok(())
+    }
+
+    /// Get the slice of the operator share, in basis points, paid to
+    /// referrers.
+    pub fn get_referral_share_bps(env: Env) -> u32 {
+        get_referral_share_bps(&env)
+    }
+
+    /// Admin-only: set the referral share, in basis points of the operator
+    /// share.
+    pub fn set_referral_share_bps(env: Env, share_bps: u32) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        if share_bps > MAX_BPS {
+            return Err(SynapseError::InvalidShareBps);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ReferralShareBps, &share_bps);
+        Ok(())
+    }
+
+    /// Get the slice of a derivative's contributor share, in basis points,
+    /// paid to the parent plan's contributor.
+    pub fn get_royalty_bps(env: Env) -> u32 {
+        get_royalty_bps(&env)
+    }
+
+    /// Admin-only: set the derivative royalty, in basis points of the
+    /// contributor share.
+    pub fn set_royalty_bps(env: Env, royalty_bps: u32) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        if royalty_bps > MAX_BPS {
+            return Err(SynapseError::InvalidShareBps);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::RoyaltyBps, &royalty_bps);
+        Ok(())
+    }
+
+    /// Get how purchase revenue is settled.
+    pub fn get_settlement_mode(env: Env) -> SettlementMode {
+        earnings::get_settlement_mode(&env)
+    }
+
+    /// Admin-only: switch between pushing shares on purchase and accruing
+    /// them for recipients to claim. Existing balances stay claimable.
+    pub fn set_settlement_mode(env: Env, mode: SettlementMode) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        env.storage()
+            .instance()
+            .set(&DataKey::SettlementMode, &mode);
+        Ok(())
+    }
+
+    /// Get the accrued earnings in `token` an address can claim.
+    pub fn get_claimable(env: Env, address: Address, token: Address) -> i128 {
+        earnings::get_claimable(&env, &address, &token)
+    }
+
+    /// Withdraw all accrued earnings in `token` to `address`. Returns the
+    /// amount paid.
+    pub fn claim(env: Env, address: Address, token: Address) -> Result<i128, SynapseError> {
+        address.require_auth();
+        let amount = earnings::take_claimable(&env, &address, &token)?;
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &address, &amount);
+
+        emit_claimed(&env, &address, &token, amount);
+        Ok(amount)
+    }
+
+    /// Get the subscription product on sale, if any.
+    pub fn get_subscription(env: Env) -> Option<SubscriptionProduct> {
+        subscription::get_product(&env)
+    }
+
+    /// Admin-only: put a subscription product on sale, or withdraw it with
+    /// `None`. The duration also sets the accounting period and cannot be
+    /// changed once configured.
+    pub fn set_subscription(
+        env: Env,
+        product: Option<SubscriptionProduct>,
+    ) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        subscription::set_product(&env, &product)?;
+        emit_subscription_set(&env, &product);
+        Ok(())
+    }
+
+    /// Buy a subscription pass, or extend an active one. While it lasts,
+    /// `purchase_plan` records a recall instead of charging.
+    pub fn buy_subscription(env: Env, buyer: Address) -> Result<SubscriptionPass, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::PurchasePlan)?;
+        let pass = subscription::buy_pass(&env, &buyer)?;
+        emit_subscription_bought(&env, &buyer, &pass);
+        Ok(pass)
+    }
+
+    /// Get a buyer's subscription pass, including an expired one.
+    pub fn get_pass(env: Env, buyer: Address) -> Option<SubscriptionPass> {
+        subscription::get_pass(&env, &buyer)
+    }
+
+    /// Get the index of the current subscription accounting period.
+    pub fn get_subscription_period(env: Env) -> Result<u32, SynapseError> {
+        subscription::current_period(&env)
+    }
+
+    /// Get the contributor income pooled in `token` for a period.
+    pub fn get_subscription_pool(env: Env, period: u32, token: Address) -> i128 {
+        subscription::get_pool(&env, period, &token)
+    }
+
+    /// Get recalls made under passes in a period, for one plan or in total.
+    pub fn get_recalls(env: Env, period: u32, plan_id: Option<BytesN<16>>) -> u32 {
+        subscription::get_recalls(&env, period, &plan_id)
+    }
+
+    /// Allocate a finished period's pool to the given plans in proportion to
+    /// their recalls, crediting each plan's beneficiaries with claimable
+    /// earnings. Anyone may call this; plans are paid at most once per
+    /// period. Returns the amount credited.
+    pub fn distribute_subscription(
+        env: Env,
+        period: u32,
+        token: Address,
+        plan_ids: Vec<BytesN<16>>,
+    ) -> Result<i128, SynapseError> {
+        let amount = subscription::distribute(&env, period, &token, &plan_ids)?;
+        emit_subscription_distributed(&env, period, &token, amount);
+        Ok(amount)
+    }
+
+    /// Get a buyer's prepaid balance in `token`.
+    pub fn get_balance(env: Env, buyer: Address, token: Address) -> i128 {
+        credits::get_balance(&env, &buyer, &token)
+    }
+
+    /// Prepay `amount` of an accepted token into the contract. Later
+    /// purchases in that token are debited from this balance when it covers
+    /// the price. Returns the new balance.
+    pub fn deposit(
+        env: Env,
+        buyer: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<i128, SynapseError> {
+        buyer.require_auth();
+        tokens::require_accepted(&env, &token)?;
+        let balance = credits::add_balance(&env, &buyer, &token, amount)?;
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&buyer, &env.current_contract_address(), &amount);
+
+        emit_deposited(&env, &buyer, &token, amount, balance);
+        Ok(balance)
+    }
+
+    /// Withdraw unused prepaid funds. Works for tokens that have since been
+    /// removed from the allowlist. Returns the remaining balance.
+    pub fn withdraw(
+        env: Env,
+        buyer: Address,
+        token: Address,
+        amount: i128,
+    ) -> Result<i128, SynapseError> {
+        buyer.require_auth();
+        let balance = credits::sub_balance(&env, &buyer, &token, amount)?;
+
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&env.current_contract_address(), &buyer, &amount);
+
+        emit_withdrawn(&env, &buyer, &token, amount, balance);
+        Ok(balance)
+    }
+
+    /// Get the buyer's payment channel for `token`, if open.
+    pub fn get_channel(env: Env, buyer: Address, token: Address) -> Option<Channel> {
+        channel::get_channel(&env, &buyer, &token)
+    }
+
+    /// Escrow `deposit` into a payment channel. The buyer then signs
+    /// cumulative vouchers off-chain with `signer`'s ed25519 key, and the
+    /// operator or a contributor redeems the latest one on-chain.
+    pub fn open_channel(
+        env: Env,
+        buyer: Address,
+        token: Address,
+        signer: BytesN<32>,
+        deposit: i128,
+    ) -> Result<Channel, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::PurchasePlan)?;
+        let channel = channel::open_channel(&env, &buyer, &token, &signer, deposit)?;
+        emit_channel_opened(&env, &buyer, &token, &channel);
+        Ok(channel)
+    }
+
+    /// Operator or a contributor of a settled plan: redeem a signed voucher,
+    /// paying for every plan it adds since the last redemption out of the
+    /// channel deposit. Earlier vouchers are superseded.
+    pub fn redeem_voucher(
+        env: Env,
+        redeemer: Address,
+        voucher: Voucher,
+        signature: BytesN<64>,
+    ) -> Result<Vec<PurchaseRecord>, SynapseError> {
+        pause::require_not_paused(&env, PausableOp::PurchasePlan)?;
+        let records = channel::redeem_voucher(&env, &redeemer, &voucher, &signature)?;
+        emit_voucher_redeemed(&env, &redeemer, &voucher, records.len());
+        Ok(records)
+    }
+
+    /// Buyer only: start closing the channel. Payees have
+    /// `CHANNEL_DISPUTE_WINDOW` ledgers to redeem their latest voucher.
+    /// Returns the last ledger redemptions are accepted.
+    pub fn close_channel(env: Env, buyer: Address, token: Address) -> Result<u32, SynapseError> {
+        let closes_at = channel::close_channel(&env, &buyer, &token)?;
+        emit_channel_closing(&env, &buyer, &token, closes_at);
+        Ok(closes_at)
+    }
+
+    /// Buyer only: after the dispute window, refund the unredeemed deposit
+    /// and delete the channel. Returns the amount refunded.
+    pub fn finalize_channel(
+        env: Env,
+        buyer: Address,
+        token: Address,
+    ) -> Result<i128, SynapseError> {
+        let refund = channel::finalize_channel(&env, &buyer, &token)?;
+        emit_channel_finalized(&env, &buyer, &token, refund);
+        Ok(refund)
+    }
+
+    /// Get the share change awaiting its timelock, if any.
+    pub fn get_pending_share_change(env: Env) -> Option<ShareChange> {
+        governance::get_pending_share_change(&env)
+    }
+
+    /// Get the number of ledgers a share change waits before it can execute.
+    pub fn get_share_change_delay(env: Env) -> u32 {
+        governance::get_share_change_delay(&env)
+    }
+
+    /// Admin-only: set the share change timelock, in ledgers. Applies to
+    /// proposals made after the call.
+    pub fn set_share_change_delay(env: Env, delay_ledgers: u32) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        governance::set_share_change_delay(&env, delay_ledgers)
+    }
+
+    /// Admin-only: propose a new contributor share in basis points. It can be
+    /// executed once the timelock has elapsed.
+    pub fn propose_share_change(env: Env, new_share_bps: u32) -> Result<ShareChange, SynapseError> {
+        require_admin(&env)?;
+        let old_share_bps = get_contributor_share_bps(&env)?;
+        let change = governance::propose_share_change(&env, new_share_bps)?;
+        emit_share_change_proposed(&env, old_share_bps, &change);
+        Ok(change)
+    }
+
+    /// Admin-only: apply the pending share change after its timelock.
+    pub fn execute_share_change(env: Env) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        let old_share_bps = get_contributor_share_bps(&env)?;
+        let change = governance::execute_share_change(&env)?;
+        emit_share_change_executed(&env, old_share_bps, change.new_share_bps);
+        Ok(())
+    }
+
+    /// Admin-only: drop the pending share change.
+    pub fn cancel_share_change(env: Env) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        let change = governance::cancel_share_change(&env)?;
+        emit_share_change_cancelled(&env, &change);
+        Ok(())
+    }
+
+    /// Get the (floor, ceiling) plan prices must fall within, in stroops.
+    pub fn get_price_bounds(env: Env) -> (i128, i128) {
+        get_price_bounds(&env)
+    }
+
+    /// Admin-only: set the floor and ceiling for plan prices.
+    pub fn set_price_bounds(
+        env: Env,
+        min_price: i128,
+        max_price: i128,
+    ) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        if min_price <= 0 || min_price > max_price {
+            return Err(SynapseError::InvalidPriceBounds);
+        }
+        env.storage().instance().set(&DataKey::MinPrice, &min_price);
+        env.storage().instance().set(&DataKey::MaxPrice, &max_price);
+        Ok(())
+    }
+
+    /// Get the (floor, ceiling) plan prices in `token` must fall within, or
+    /// `None` if the admin has not bounded that token, so it cannot be priced
+    /// in yet.
+    pub fn get_token_price_bounds(
+        env: Env,
+        token: Address,
+    ) -> Result<Option<(i128, i128)>, SynapseError> {
+        if token == get_native_token(&env)? {
+            return Ok(Some(get_price_bounds(&env)));
+        }
+        Ok(tokens::get_price_bounds(&env, &token))
+    }
+
+    /// Admin-only: set the floor and ceiling for plan prices in `token`.
+    pub fn set_token_price_bounds(
+        env: Env,
+        token: Address,
+        min_price: i128,
+        max_price: i128,
+    ) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        if min_price <= 0 || min_price > max_price {
+            return Err(SynapseError::InvalidPriceBounds);
+        }
+        if token == get_native_token(&env)? {
+            env.storage().instance().set(&DataKey::MinPrice, &min_price);
+            env.storage().instance().set(&DataKey::MaxPrice, &max_price);
+        } else {
+            tokens::set_price_bounds(&env, &token, min_price, max_price);
+        }
+        Ok(())
     }
 
     /// Admin-only: update operator address.
-    pub fn set_operator(env: Env, new_operator: Address) {
-        require_admin(&env);
+    pub fn set_operator(env: Env, new_operator: Address) -> Result<(), SynapseError> {
+        require_admin(&env)?;
         env.storage()
             .instance()
             .set(&DataKey::OperatorAddress, &new_operator);
+        Ok(())
+    }
+
+    /// Get the current admin address.
+    pub fn get_admin(env: Env) -> Result<Address, SynapseError> {
+        get_admin(&env)
+    }
+
+    /// Get the admin proposed via `propose_admin`, if any.
+    pub fn get_pending_admin(env: Env) -> Option<Address> {
+        get_pending_admin(&env)
+    }
+
+    /// Admin-only: propose a new admin. Takes effect once they call `accept_admin`.
+    /// A new proposal replaces any earlier one.
+    pub fn propose_admin(env: Env, new_admin: Address) -> Result<(), SynapseError> {
+        let admin = require_admin(&env)?;
+        set_pending_admin(&env, &new_admin);
+        emit_admin_proposed(&env, &admin, &new_admin);
+        Ok(())
+    }
+
+    /// Pending admin only: accept the proposal and become admin.
+    pub fn accept_admin(env: Env) -> Result<(), SynapseError> {
+        let (old_admin, new_admin) = take_pending_admin(&env)?;
+        emit_admin_accepted(&env, &old_admin, &new_admin);
+        Ok(())
+    }
+
+    /// Admin-only: withdraw a pending admin proposal.
+    pub fn cancel_admin_transfer(env: Env) -> Result<(), SynapseError> {
+        let admin = require_admin(&env)?;
+        let pending = get_pending_admin(&env).ok_or(SynapseError::NoPendingAdmin)?;
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        emit_admin_cancelled(&env, &admin, &pending);
+        Ok(())
+    }
+
+    /// Admin-only: replace the contract code, keeping all storage.
+    /// Follow with `migrate` if the new code changes the storage layout.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), SynapseError> {
+        let admin = require_admin(&env)?;
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+        emit_upgraded(&env, &admin, &new_wasm_hash);
+        Ok(())
+    }
+
+    /// Admin-only: rewrite the given plans into the current storage layout and
+    /// record the current schema version. Plans already migrated are skipped,
+    /// so large deployments can be migrated in batches. Returns the number of
+    /// plans rewritten.
+    pub fn migrate(env: Env, plan_ids: Vec<BytesN<16>>) -> Result<u32, SynapseError> {
+        require_admin(&env)?;
+        let from_version = migration::get_schema_version(&env);
+        migration::migrate_instance(&env, from_version);
+        let migrated = migration::migrate_plans(&env, &plan_ids)?;
+        migration::set_schema_version(&env, SCHEMA_VERSION);
+        emit_migrated(&env, from_version, SCHEMA_VERSION, migrated);
+        Ok(migrated)
+    }
+
+    /// Get the storage layout version this contract's data is in.
+    pub fn get_schema_version(env: Env) -> u32 {
+        migration::get_schema_version(&env)
+    }
+
+    /// Admin-only: designate (or clear) a guardian who may pause operations.
+    pub fn set_guardian(env: Env, guardian: Option<Address>) -> Result<(), SynapseError> {
+        require_admin(&env)?;
+        match guardian {
+            Some(guardian) => env.storage().instance().set(&DataKey::Guardian, &guardian),
+            None => env.storage().instance().remove(&DataKey::Guardian),
+        }
+        Ok(())
+    }
+
+    /// Get the current guardian, if any.
+    pub fn get_guardian(env: Env) -> Option<Address> {
+        pause::get_guardian(&env)
+    }
+
+    /// Admin or guardian: halt one operation. Read-only getters keep working.
+    pub fn pause(env: Env, caller: Address, op: PausableOp) -> Result<(), SynapseError> {
+        pause::require_pauser(&env, &caller)?;
+        pause::set_paused(&env, &op, true);
+        emit_paused(&env, &caller, &op);
+        Ok(())
+    }
+
+    /// Admin-only: resume a paused operation.
+    pub fn unpause(env: Env, op: PausableOp) -> Result<(), SynapseError> {
+        let admin = require_admin(&env)?;
+        pause::set_paused(&env, &op, false);
+        emit_unpaused(&env, &admin, &op);
+        Ok(())
+    }
+
+    /// Check whether an operation is currently paused.
+    pub fn is_paused(env: Env, op: PausableOp) -> bool {
+        pause::is_paused(&env, &op)
     }
 }
 
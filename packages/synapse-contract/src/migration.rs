@@ -0,0 +1,205 @@
+use soroban_sdk::{
+    contracttype, symbol_short, Address, BytesN, Env, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+use crate::access::{get_entitlement, grant_access};
+use crate::admin::get_native_token;
+use crate::errors::SynapseError;
+use crate::plan::{load_plan, update_plan};
+use crate::storage_keys::DataKey;
+use crate::types::{LicenseModel, Payout, PlanMeta, PurchaseRecord, StorageTier};
+
+/// Storage layout version written by this build of the contract.
+///
+/// - 1: original layout (no `SchemaVersion` key, `PlanMeta` without `price`,
+///   contributor share as a whole percent)
+/// - 2: current layout (`PlanMeta.price`, contributor share in basis points,
+///   `PurchaseRecord` payouts, token and referrer, per-buyer `Access`
+///   entitlements)
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Price assigned to plans stored before per-plan pricing existed (1 XLM).
+const LEGACY_PLAN_PRICE: i128 = 10_000_000;
+
+/// Number of fields in a `PlanMetaV1` entry.
+const PLAN_V1_FIELDS: u32 = 14;
+
+/// `PlanMeta` as stored by schema version 1.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PlanMetaV1 {
+    pub id: BytesN<16>,
+    pub title: String,
+    pub description: String,
+    pub content_hash: BytesN<32>,
+    pub ipfs_cid: String,
+    pub tags: Vec<String>,
+    pub domain: String,
+    pub language: String,
+    pub framework: String,
+    pub contributor: Address,
+    pub quality_score: u32,
+    pub purchase_count: u32,
+    pub tier: StorageTier,
+    pub created_at: u64,
+}
+
+/// `PurchaseRecord` as stored by schema version 1.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PurchaseRecordV1 {
+    pub buyer: Address,
+    pub amount_stroops: i128,
+    pub contributor_share: i128,
+    pub operator_share: i128,
+    pub ledger: u32,
+}
+
+/// Deployments that predate versioning have no key and are version 1.
+pub fn get_schema_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SchemaVersion)
+        .unwrap_or(1)
+}
+
+pub fn set_schema_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&DataKey::SchemaVersion, &version);
+}
+
+/// Rewrites instance-storage config written by older versions.
+pub fn migrate_instance(env: &Env, from_version: u32) {
+    if from_version < 2 {
+        let legacy: Option<u32> = env.storage().instance().get(&DataKey::ContributorSharePct);
+        if let Some(share_pct) = legacy {
+            env.storage()
+                .instance()
+                .set(&DataKey::ContributorShareBps, &(share_pct * 100));
+            env.storage()
+                .instance()
+                .remove(&DataKey::ContributorSharePct);
+        }
+        // Pending proposals were denominated in percent; they must be re-proposed
+        env.storage()
+            .instance()
+            .remove(&DataKey::PendingShareChange);
+    }
+}
+
+/// Rewrites each plan's entries still in an old layout. Entries already in
+/// the current layout are left alone, so batches can be retried or overlap.
+/// Returns how many entries were rewritten.
+pub fn migrate_plans(env: &Env, plan_ids: &Vec<BytesN<16>>) -> Result<u32, SynapseError> {
+    let mut migrated = 0u32;
+    for plan_id in plan_ids.iter() {
+        if migrate_plan_entry(env, &plan_id)? {
+            migrated += 1;
+        }
+        if migrate_purchases_entry(env, &plan_id)? {
+            migrated += 1;
+        }
+        backfill_access(env, &plan_id)?;
+    }
+    Ok(migrated)
+}
+
+fn migrate_plan_entry(env: &Env, plan_id: &BytesN<16>) -> Result<bool, SynapseError> {
+    // Inspect the raw field map: decoding into the wrong struct traps
+    let fields: Map<Symbol, Val> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Plan(plan_id.clone()))
+        .ok_or(SynapseError::PlanNotFound)?;
+
+    if fields.contains_key(symbol_short!("price")) {
+        return Ok(false);
+    }
+    if fields.len() != PLAN_V1_FIELDS {
+        return Err(SynapseError::InvalidPlanLayout);
+    }
+    let old = PlanMetaV1::try_from_val(env, &fields.to_val())
+        .map_err(|_| SynapseError::InvalidPlanLayout)?;
+    update_plan(env, &upgrade_plan_v1(old));
+    Ok(true)
+}
+
+/// Legacy records were all paid in the native token and predate co-authors
+/// and referrals, so the whole contributor share went to the plan's
+/// contributor.
+fn migrate_purchases_entry(env: &Env, plan_id: &BytesN<16>) -> Result<bool, SynapseError> {
+    let key = DataKey::Purchases(plan_id.clone());
+    let records: Vec<Map<Symbol, Val>> = match env.storage().persistent().get(&key) {
+        Some(records) => records,
+        None => return Ok(false),
+    };
+    // Records are only ever appended by one layout at a time
+    match records.get(0) {
+        Some(first) if !first.contains_key(symbol_short!("token")) => {}
+        _ => return Ok(false),
+    }
+
+    let contributor = load_plan(env, plan_id)?.contributor;
+    let native_token = get_native_token(env)?;
+    let mut upgraded: Vec<PurchaseRecord> = Vec::new(env);
+    for fields in records.iter() {
+        let old = PurchaseRecordV1::try_from_val(env, &fields.to_val())
+            .map_err(|_| SynapseError::InvalidPlanLayout)?;
+        upgraded.push_back(PurchaseRecord {
+            buyer: old.buyer,
+            amount_stroops: old.amount_stroops,
+            contributor_share: old.contributor_share,
+            operator_share: old.operator_share,
+            payouts: Vec::from_array(
+                env,
+                [Payout {
+                    recipient: contributor.clone(),
+                    amount: old.contributor_share,
+                }],
+            ),
+            token: native_token.clone(),
+            referrer: None,
+            referral_share: 0,
+            ledger: old.ledger,
+        });
+    }
+    env.storage().persistent().set(&key, &upgraded);
+    Ok(true)
+}
+
+/// Grants access to everyone in the plan's purchase history. Entitlements
+/// are new entries rather than rewrites, so they are not counted.
+fn backfill_access(env: &Env, plan_id: &BytesN<16>) -> Result<(), SynapseError> {
+    let records: Vec<PurchaseRecord> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Purchases(plan_id.clone()))
+        .unwrap_or(Vec::new(env));
+    for record in records.iter() {
+        if get_entitlement(env, plan_id, &record.buyer).is_none() {
+            grant_access(env, plan_id, &record.buyer, &LicenseModel::Perpetual)?;
+        }
+    }
+    Ok(())
+}
+
+fn upgrade_plan_v1(old: PlanMetaV1) -> PlanMeta {
+    PlanMeta {
+        id: old.id,
+        title: old.title,
+        description: old.description,
+        content_hash: old.content_hash,
+        ipfs_cid: old.ipfs_cid,
+        tags: old.tags,
+        domain: old.domain,
+        language: old.language,
+        framework: old.framework,
+        contributor: old.contributor,
+        quality_score: old.quality_score,
+        price: LEGACY_PLAN_PRICE,
+        purchase_count: old.purchase_count,
+        tier: old.tier,
+        created_at: old.created_at,
+    }
+}
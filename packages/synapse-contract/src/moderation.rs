@@ -0,0 +1,216 @@
+use soroban_sdk::{Address, BytesN, Env, Vec};
+
+use crate::access::get_entitlement;
+use crate::admin::get_admin;
+use crate::errors::SynapseError;
+use crate::plan::{get_status, get_versions, load_plan, set_status, TTL_HOT};
+use crate::storage_keys::{DataKey, DataKeyExt};
+use crate::types::{AppealStatus, PlanFlag, PlanStatus, Takedown};
+
+pub fn get_moderator(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Moderator)
+}
+
+/// Takedowns are open to the admin and the moderator.
+pub fn require_moderator(env: &Env, caller: &Address) -> Result<(), SynapseError> {
+    caller.require_auth();
+    if *caller == get_admin(env)? || Some(caller.clone()) == get_moderator(env) {
+        return Ok(());
+    }
+    Err(SynapseError::Unauthorized)
+}
+
+pub fn is_blocked(env: &Env, content_hash: &BytesN<32>) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKeyExt::BlockedHash(content_hash.clone()))
+}
+
+pub fn require_not_blocked(env: &Env, content_hash: &BytesN<32>) -> Result<(), SynapseError> {
+    if is_blocked(env, content_hash) {
+        return Err(SynapseError::ContentBlocked);
+    }
+    Ok(())
+}
+
+pub fn get_takedown(env: &Env, plan_id: &BytesN<16>) -> Option<Takedown> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::Takedown(plan_id.clone()))
+}
+
+fn save_takedown(env: &Env, plan_id: &BytesN<16>, takedown: &Takedown) {
+    let key = DataKeyExt::Takedown(plan_id.clone());
+    env.storage().persistent().set(&key, takedown);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_HOT, TTL_HOT);
+}
+
+/// Blocks or unblocks every content hash the plan has ever had.
+fn set_blocked(env: &Env, plan_id: &BytesN<16>, blocked: bool) -> Result<(), SynapseError> {
+    let plan = load_plan(env, plan_id)?;
+    for version in get_versions(env, &plan).iter() {
+        let key = DataKeyExt::BlockedHash(version.content_hash);
+        if blocked {
+            env.storage().persistent().set(&key, plan_id);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, TTL_HOT, TTL_HOT);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+    Ok(())
+}
+
+/// Delists the plan and blocklists its content. Returns the plan's status
+/// before the takedown.
+pub fn takedown(
+    env: &Env,
+    moderator: &Address,
+    plan_id: &BytesN<16>,
+    reason: u32,
+) -> Result<PlanStatus, SynapseError> {
+    if get_takedown(env, plan_id).is_some() {
+        return Err(SynapseError::InvalidStatusTransition);
+    }
+    let old_status = get_status(env, plan_id);
+    set_blocked(env, plan_id, true)?;
+    set_status(env, plan_id, &PlanStatus::Removed(reason));
+    save_takedown(
+        env,
+        plan_id,
+        &Takedown {
+            moderator: moderator.clone(),
+            reason,
+            ledger: env.ledger().sequence(),
+            appeal: AppealStatus::NotFiled,
+            prior_status: old_status.clone(),
+        },
+    );
+    Ok(old_status)
+}
+
+/// Files the contributor's one appeal against a takedown.
+pub fn file_appeal(
+    env: &Env,
+    plan_id: &BytesN<16>,
+    statement_hash: &BytesN<32>,
+) -> Result<(), SynapseError> {
+    let mut takedown = get_takedown(env, plan_id).ok_or(SynapseError::AppealNotAllowed)?;
+    if takedown.appeal != AppealStatus::NotFiled {
+        return Err(SynapseError::AppealNotAllowed);
+    }
+    takedown.appeal = AppealStatus::Pending(statement_hash.clone());
+    save_takedown(env, plan_id, &takedown);
+    Ok(())
+}
+
+/// Upholding an appeal restores the plan's status from before the takedown
+/// and lifts the blocklist entries; rejecting it makes the takedown final.
+/// Returns the restored status if the appeal was upheld.
+pub fn resolve_appeal(
+    env: &Env,
+    plan_id: &BytesN<16>,
+    upheld: bool,
+) -> Result<Option<PlanStatus>, SynapseError> {
+    let mut takedown = get_takedown(env, plan_id).ok_or(SynapseError::NoPendingAppeal)?;
+    if !matches!(takedown.appeal, AppealStatus::Pending(_)) {
+        return Err(SynapseError::NoPendingAppeal);
+    }
+    if !upheld {
+        takedown.appeal = AppealStatus::Rejected;
+        save_takedown(env, plan_id, &takedown);
+        return Ok(None);
+    }
+    set_blocked(env, plan_id, false)?;
+    set_status(env, plan_id, &takedown.prior_status);
+    env.storage()
+        .persistent()
+        .remove(&DataKeyExt::Takedown(plan_id.clone()));
+    Ok(Some(takedown.prior_status))
+}
+
+/// Buyer flags that quarantine a plan. Defaults to zero, which disables
+/// automatic quarantine.
+pub fn get_flag_threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::FlagThreshold)
+        .unwrap_or(0)
+}
+
+pub fn get_flags(env: &Env, plan_id: &BytesN<16>) -> Vec<PlanFlag> {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::Flags(plan_id.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Flags already weighed by the admin, who activated the plan after them.
+fn get_flags_reviewed(env: &Env, plan_id: &BytesN<16>) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::FlagsReviewed(plan_id.clone()))
+        .unwrap_or(0)
+}
+
+/// Marks the plan's flags so far as reviewed, so only later flags count
+/// towards quarantining it again.
+pub fn mark_flags_reviewed(env: &Env, plan_id: &BytesN<16>) {
+    let count = get_flags(env, plan_id).len();
+    if count == 0 {
+        return;
+    }
+    let key = DataKeyExt::FlagsReviewed(plan_id.clone());
+    env.storage().persistent().set(&key, &count);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_HOT, TTL_HOT);
+}
+
+/// Records a buyer's one flag against a plan they bought. Returns the flag
+/// count, and the plan's prior status if this flag quarantined it.
+pub fn flag(
+    env: &Env,
+    buyer: &Address,
+    plan_id: &BytesN<16>,
+    reason: u32,
+) -> Result<(u32, Option<PlanStatus>), SynapseError> {
+    load_plan(env, plan_id)?;
+    if get_entitlement(env, plan_id, buyer).is_none() {
+        return Err(SynapseError::NotPurchased);
+    }
+    let flagged_key = DataKeyExt::Flagged(plan_id.clone(), buyer.clone());
+    if env.storage().persistent().has(&flagged_key) {
+        return Err(SynapseError::AlreadyFlagged);
+    }
+    env.storage().persistent().set(&flagged_key, &true);
+    env.storage()
+        .persistent()
+        .extend_ttl(&flagged_key, TTL_HOT, TTL_HOT);
+
+    let mut flags = get_flags(env, plan_id);
+    flags.push_back(PlanFlag {
+        buyer: buyer.clone(),
+        reason,
+        ledger: env.ledger().sequence(),
+    });
+    let flags_key = DataKeyExt::Flags(plan_id.clone());
+    env.storage().persistent().set(&flags_key, &flags);
+    env.storage()
+        .persistent()
+        .extend_ttl(&flags_key, TTL_HOT, TTL_HOT);
+
+    // Only listed plans are quarantined; delisted ones already refuse sales
+    let count = flags.len();
+    let unreviewed = count - get_flags_reviewed(env, plan_id);
+    let threshold = get_flag_threshold(env);
+    let old_status = get_status(env, plan_id);
+    if threshold > 0 && unreviewed >= threshold && old_status == PlanStatus::Active {
+        set_status(env, plan_id, &PlanStatus::Quarantined);
+        return Ok((count, Some(old_status)));
+    }
+    Ok((count, None))
+}
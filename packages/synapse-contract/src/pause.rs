@@ -0,0 +1,39 @@
+use soroban_sdk::{Address, Env};
+
+use crate::admin::get_admin;
+use crate::errors::SynapseError;
+use crate::storage_keys::DataKey;
+use crate::types::PausableOp;
+
+pub fn get_guardian(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Guardian)
+}
+
+pub fn is_paused(env: &Env, op: &PausableOp) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKey::Paused(op.clone()))
+        .unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, op: &PausableOp, paused: bool) {
+    env.storage()
+        .instance()
+        .set(&DataKey::Paused(op.clone()), &paused);
+}
+
+pub fn require_not_paused(env: &Env, op: PausableOp) -> Result<(), SynapseError> {
+    if is_paused(env, &op) {
+        return Err(SynapseError::Paused);
+    }
+    Ok(())
+}
+
+/// Pausing is open to the admin and the guardian; unpausing is admin-only.
+pub fn require_pauser(env: &Env, caller: &Address) -> Result<(), SynapseError> {
+    caller.require_auth();
+    if *caller == get_admin(env)? || Some(caller.clone()) == get_guardian(env) {
+        return Ok(());
+    }
+    Err(SynapseError::Unauthorized)
+}
@@ -1,13 +1,24 @@
-use soroban_sdk::{Address, BytesN, Env, Vec};
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env, String, Vec};
 
-use crate::storage_keys::DataKey;
-use crate::types::{PlanMeta, StorageTier};
+use crate::admin::get_price_bounds;
+use crate::errors::SynapseError;
+use crate::moderation::get_takedown;
+use crate::storage_keys::{DataKey, DataKeyExt};
+use crate::split::MAX_BPS;
+use crate::types::{
+    Beneficiary, LicenseModel, PlanMeta, PlanMetaUpdate, PlanStatus, PlanVersion, StorageTier,
+};
 
 /// TTL ledger counts by tier (approximate days at ~5s/ledger)
-const TTL_HOT: u32 = 535_680; // ~31 days
+pub const TTL_HOT: u32 = 535_680; // ~31 days
 const TTL_COLD: u32 = 267_840; // ~15 days
 const TTL_ARCHIVE: u32 = 120_960; // ~7 days
 
+/// Upper bound on co-authors per plan, keeping purchases within resource limits
+const MAX_BENEFICIARIES: u32 = 10;
+/// Most ancestors of a derivative plan that receive royalties.
+pub const MAX_ROYALTY_DEPTH: u32 = 3;
+
 pub fn save_plan(env: &Env, plan: &PlanMeta) {
     env.storage()
         .persistent()
@@ -19,15 +30,7 @@ pub fn save_plan(env: &Env, plan: &PlanMeta) {
         .set(&DataKey::ContentHash(plan.content_hash.clone()), &true);
 
     // Update contributor's plan list
-    let mut contrib_plans: Vec<BytesN<16>> = env
-        .storage()
-        .persistent()
-        .get(&DataKey::ContribPlans(plan.contributor.clone()))
-        .unwrap_or(Vec::new(env));
-    contrib_plans.push_back(plan.id.clone());
-    env.storage()
-        .persistent()
-        .set(&DataKey::ContribPlans(plan.contributor.clone()), &contrib_plans);
+    add_contributor_plan(env, &plan.contributor, &plan.id);
 
     // Increment plan count
     let count: u32 = env
@@ -43,12 +46,295 @@ pub fn save_plan(env: &Env, plan: &PlanMeta) {
     bump_ttl(env, &plan.id, &plan.tier);
 }
 
+fn add_contributor_plan(env: &Env, contributor: &Address, plan_id: &BytesN<16>) {
+    let mut contrib_plans: Vec<BytesN<16>> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ContribPlans(contributor.clone()))
+        .unwrap_or(Vec::new(env));
+    contrib_plans.push_back(plan_id.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::ContribPlans(contributor.clone()), &contrib_plans);
+}
+
+pub fn get_status(env: &Env, plan_id: &BytesN<16>) -> PlanStatus {
+    env.storage()
+        .persistent()
+        .get(&DataKeyExt::Status(plan_id.clone()))
+        .unwrap_or(PlanStatus::Active)
+}
+
+/// Active is the default and is not stored.
+pub fn set_status(env: &Env, plan_id: &BytesN<16>, status: &PlanStatus) {
+    let key = DataKeyExt::Status(plan_id.clone());
+    if *status == PlanStatus::Active {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, status);
+    }
+}
+
+pub fn require_active(env: &Env, plan_id: &BytesN<16>) -> Result<(), SynapseError> {
+    match get_status(env, plan_id) {
+        PlanStatus::Active => Ok(()),
+        _ => Err(SynapseError::PlanNotActive),
+    }
+}
+
+/// Whether new plans await admin approval. Defaults to listing them at once.
+pub fn get_plan_review(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&DataKeyExt::PlanReview)
+        .unwrap_or(false)
+}
+
+/// An update must change something, and cannot blank the title.
+pub fn validate_meta_update(update: &PlanMetaUpdate) -> Result<(), SynapseError> {
+    let empty = update.title.is_none()
+        && update.description.is_none()
+        && update.tags.is_none()
+        && update.domain.is_none()
+        && update.language.is_none()
+        && update.framework.is_none();
+    if empty || update.title.as_ref().is_some_and(|t| t.is_empty()) {
+        return Err(SynapseError::InvalidMetaUpdate);
+    }
+    Ok(())
+}
+
+pub fn apply_meta_update(plan: &mut PlanMeta, update: &PlanMetaUpdate) {
+    if let Some(title) = &update.title {
+        plan.title = title.clone();
+    }
+    if let Some(description) = &update.description {
+        plan.description = description.clone();
+    }
+    if let Some(tags) = &update.tags {
+        plan.tags = tags.clone();
+    }
+    if let Some(domain) = &update.domain {
+        plan.domain = domain.clone();
+    }
+    if let Some(language) = &update.language {
+        plan.language = language.clone();
+    }
+    if let Some(framework) = &update.framework {
+        plan.framework = framework.clone();
+    }
+}
+
+/// The plan's content history, oldest first. Plans never updated have no
+/// stored log; their only version is the content in `PlanMeta`.
+pub fn get_versions(env: &Env, plan: &PlanMeta) -> Vec<PlanVersion> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Versions(plan.id.clone()))
+        .unwrap_or(Vec::from_array(
+            env,
+            [PlanVersion {
+                version: 1,
+                content_hash: plan.content_hash.clone(),
+                ipfs_cid: plan.ipfs_cid.clone(),
+                created_at: plan.created_at,
+            }],
+        ))
+}
+
+/// Appends a new content version and makes it the plan's current content.
+/// Earlier hashes stay registered, so no other plan can republish them.
+/// Returns the new version.
+pub fn add_version(
+    env: &Env,
+    plan: &mut PlanMeta,
+    content_hash: &BytesN<32>,
+    ipfs_cid: &String,
+) -> Result<PlanVersion, SynapseError> {
+    if content_exists(env, content_hash) {
+        return Err(SynapseError::ContentExists);
+    }
+    let mut versions = get_versions(env, plan);
+    let version = PlanVersion {
+        version: versions.len() + 1,
+        content_hash: content_hash.clone(),
+        ipfs_cid: ipfs_cid.clone(),
+        created_at: env.ledger().timestamp(),
+    };
+    versions.push_back(version.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::Versions(plan.id.clone()), &versions);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ContentHash(content_hash.clone()), &true);
+
+    plan.content_hash = content_hash.clone();
+    plan.ipfs_cid = ipfs_cid.clone();
+    update_plan(env, plan);
+    Ok(version)
+}
+
+/// Links a derivative plan to the plan it extends.
+pub fn save_parent(env: &Env, plan_id: &BytesN<16>, parent: &BytesN<16>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Parent(plan_id.clone()), parent);
+    let mut derivatives = get_derivatives(env, parent);
+    derivatives.push_back(plan_id.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey::Derivatives(parent.clone()), &derivatives);
+}
+
+pub fn get_parent(env: &Env, plan_id: &BytesN<16>) -> Option<BytesN<16>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Parent(plan_id.clone()))
+}
+
+pub fn get_derivatives(env: &Env, plan_id: &BytesN<16>) -> Vec<BytesN<16>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Derivatives(plan_id.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Contributors of the plan's ancestors, nearest first, up to
+/// `MAX_ROYALTY_DEPTH` of them. Ancestors taken down by moderation are passed
+/// over and earn no royalties.
+pub fn get_ancestor_contributors(env: &Env, plan_id: &BytesN<16>) -> Vec<Address> {
+    let mut contributors = Vec::new(env);
+    let mut next = get_parent(env, plan_id);
+    while let Some(parent_id) = next {
+        if contributors.len() == MAX_ROYALTY_DEPTH {
+            break;
+        }
+        let Some(parent) = get_plan(env, &parent_id) else {
+            break;
+        };
+        if get_takedown(env, &parent_id).is_none() {
+            contributors.push_back(parent.contributor);
+        }
+        next = get_parent(env, &parent_id);
+    }
+    contributors
+}
+
+/// Weights must be non-zero, sum to 100%, and name each address once.
+pub fn validate_beneficiaries(beneficiaries: &Vec<Beneficiary>) -> Result<(), SynapseError> {
+    if beneficiaries.len() > MAX_BENEFICIARIES {
+        return Err(SynapseError::InvalidBeneficiaries);
+    }
+    let mut total: u32 = 0;
+    for (i, b) in beneficiaries.iter().enumerate() {
+        if b.weight_bps == 0 {
+            return Err(SynapseError::InvalidBeneficiaries);
+        }
+        for other in beneficiaries.iter().skip(i + 1) {
+            if other.address == b.address {
+                return Err(SynapseError::InvalidBeneficiaries);
+            }
+        }
+        total = total.saturating_add(b.weight_bps);
+    }
+    if !beneficiaries.is_empty() && total != MAX_BPS {
+        return Err(SynapseError::InvalidBeneficiaries);
+    }
+    Ok(())
+}
+
+/// Stores a co-authored plan's beneficiaries and lists the plan under each
+/// co-author's contributor plans.
+pub fn save_beneficiaries(env: &Env, plan: &PlanMeta, beneficiaries: &Vec<Beneficiary>) {
+    if beneficiaries.is_empty() {
+        return;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::Beneficiaries(plan.id.clone()), beneficiaries);
+    for b in beneficiaries.iter() {
+        if b.address != plan.contributor {
+            add_contributor_plan(env, &b.address, &plan.id);
+        }
+    }
+}
+
+pub fn validate_license(license: &LicenseModel) -> Result<(), SynapseError> {
+    match license {
+        LicenseModel::Duration(0) => Err(SynapseError::InvalidLicense),
+        _ => Ok(()),
+    }
+}
+
+/// Perpetual licenses are the default and are not stored.
+pub fn save_license(env: &Env, plan_id: &BytesN<16>, license: &LicenseModel) {
+    if *license == LicenseModel::Perpetual {
+        return;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::License(plan_id.clone()), license);
+}
+
+pub fn get_license(env: &Env, plan_id: &BytesN<16>) -> LicenseModel {
+    env.storage()
+        .persistent()
+        .get(&DataKey::License(plan_id.clone()))
+        .unwrap_or(LicenseModel::Perpetual)
+}
+
+/// Who receives the contributor share: the stored co-authors, or the
+/// contributor alone.
+pub fn get_beneficiaries(env: &Env, plan: &PlanMeta) -> Vec<Beneficiary> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Beneficiaries(plan.id.clone()))
+        .unwrap_or(Vec::from_array(
+            env,
+            [Beneficiary {
+                address: plan.contributor.clone(),
+                weight_bps: MAX_BPS,
+            }],
+        ))
+}
+
 pub fn get_plan(env: &Env, plan_id: &BytesN<16>) -> Option<PlanMeta> {
     env.storage()
         .persistent()
         .get(&DataKey::Plan(plan_id.clone()))
 }
 
+/// Like `get_plan`, but a missing plan is an error.
+pub fn load_plan(env: &Env, plan_id: &BytesN<16>) -> Result<PlanMeta, SynapseError> {
+    get_plan(env, plan_id).ok_or(SynapseError::PlanNotFound)
+}
+
+pub fn plan_exists(env: &Env, plan_id: &BytesN<16>) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::Plan(plan_id.clone()))
+}
+
+/// Deterministic plan ID: first 16 bytes of sha256(content_hash || contributor XDR).
+pub fn derive_plan_id(env: &Env, content_hash: &BytesN<32>, contributor: &Address) -> BytesN<16> {
+    let mut preimage = Bytes::from(content_hash.clone());
+    preimage.append(&contributor.clone().to_xdr(env));
+    let digest = env.crypto().sha256(&preimage).to_array();
+
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&digest[..16]);
+    BytesN::from_array(env, &id)
+}
+
+pub fn validate_price(env: &Env, price: i128) -> Result<(), SynapseError> {
+    let (min, max) = get_price_bounds(env);
+    if price < min || price > max {
+        return Err(SynapseError::PriceOutOfBounds);
+    }
+    Ok(())
+}
+
 pub fn content_exists(env: &Env, content_hash: &BytesN<32>) -> bool {
     env.storage()
         .persistent()
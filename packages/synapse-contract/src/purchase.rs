@@ -1,37 +1,161 @@
-use soroban_sdk::{token, Address, BytesN, Env, Vec};
+use soroban_sdk::{token, Address, BytesN, Env, Map, Vec};
 
-use crate::admin::{get_contributor_share_pct, get_native_token, get_operator};
-use crate::plan::{get_plan, update_plan};
+use crate::access::grant_access;
+use crate::admin::{
+    get_contributor_share_bps, get_dust_recipient, get_operator, get_referral_share_bps,
+    get_royalty_bps,
+};
+use crate::credits;
+use crate::earnings::{credit, get_settlement_mode};
+use crate::errors::SynapseError;
+use crate::events::emit_balance_debited;
+use crate::plan::{
+    get_ancestor_contributors, get_beneficiaries, get_license, load_plan, require_active,
+    update_plan,
+};
+use crate::split::split_amount;
 use crate::storage_keys::DataKey;
-use crate::types::{PurchaseRecord, StorageTier};
+use crate::tokens::{get_plan_price, require_accepted};
+use crate::types::{
+    Beneficiary, DustRecipient, Payout, PlanMeta, PurchaseRecord, SettlementMode, StorageTier,
+};
 
+/// Charges the buyer the plan's listed price in `token`. `max_amount` is the
+/// most the buyer agreed to pay; anything below the price is rejected.
+///
+/// The price is debited from the buyer's prepaid balance when it covers the
+/// full amount, and otherwise paid from the buyer's wallet.
 pub fn execute_purchase(
     env: &Env,
     buyer: &Address,
     plan_id: &BytesN<16>,
-    amount: i128,
-) -> PurchaseRecord {
+    token: &Address,
+    max_amount: i128,
+    referrer: &Option<Address>,
+) -> Result<PurchaseRecord, SynapseError> {
+    let records = execute_batch(
+        env,
+        buyer,
+        &Vec::from_array(env, [plan_id.clone()]),
+        token,
+        max_amount,
+        referrer,
+    )?;
+    Ok(records.get_unchecked(0))
+}
+
+/// Charges the buyer for several plans at once. `max_total` caps the sum of
+/// their prices. Shares owed to the same recipient are paid in a single
+/// transfer. A `referrer` receives the referral share of each operator share.
+pub fn execute_batch(
+    env: &Env,
+    buyer: &Address,
+    plan_ids: &Vec<BytesN<16>>,
+    token: &Address,
+    max_total: i128,
+    referrer: &Option<Address>,
+) -> Result<Vec<PurchaseRecord>, SynapseError> {
     buyer.require_auth();
+    if referrer.as_ref() == Some(buyer) {
+        return Err(SynapseError::SelfReferral);
+    }
+    require_accepted(env, token)?;
+
+    let mut priced: Vec<(PlanMeta, i128)> = Vec::new(env);
+    let mut total: i128 = 0;
+    for plan_id in plan_ids.iter() {
+        if priced.iter().any(|(plan, _)| plan.id == plan_id) {
+            return Err(SynapseError::DuplicatePlan);
+        }
+        let plan = load_plan(env, &plan_id)?;
+        require_active(env, &plan_id)?;
+        let price = get_plan_price(env, &plan, token)?;
+        total = total
+            .checked_add(price)
+            .ok_or(SynapseError::ArithmeticOverflow)?;
+        priced.push_back((plan, price));
+    }
+    if max_total < total {
+        return Err(SynapseError::Underpayment);
+    }
 
-    let mut plan = get_plan(env, plan_id).expect("Plan not found");
+    // Prepaid funds are already in the contract's custody
+    let prepaid = credits::get_balance(env, buyer, token) >= total;
+    let mut owed = Map::new(env);
+    let mut records = Vec::new(env);
+    for (plan, price) in priced.iter() {
+        if prepaid {
+            let balance = credits::sub_balance(env, buyer, token, price)?;
+            emit_balance_debited(env, buyer, &plan.id, token, price, balance);
+        }
+        let record = record_purchase(env, buyer, plan, token, price, referrer)?;
+        add_owed(&mut owed, &record)?;
+        records.push_back(record);
+    }
+
+    pay_out(env, buyer, token, total, &owed, prepaid)?;
+    Ok(records)
+}
 
-    let share_pct = get_contributor_share_pct(env) as i128;
-    let contributor_share = (amount * share_pct) / 100;
-    let operator_share = amount - contributor_share;
+/// Splits `amount` between the plan's beneficiaries and the operator and
+/// records the purchase, granting or renewing the buyer's access. With
+/// `in_custody` the contract already holds the funds; otherwise they are
+/// drawn from the buyer's wallet. Perpetual owners are not charged again.
+/// The plan's status is not checked, so a voucher signed before the plan was
+/// delisted is still honoured.
+pub fn settle_purchase(
+    env: &Env,
+    buyer: &Address,
+    plan: PlanMeta,
+    token: &Address,
+    amount: i128,
+    in_custody: bool,
+) -> Result<PurchaseRecord, SynapseError> {
+    let record = record_purchase(env, buyer, plan, token, amount, &None)?;
+    let mut owed = Map::new(env);
+    add_owed(&mut owed, &record)?;
+    pay_out(env, buyer, token, amount, &owed, in_custody)?;
+    Ok(record)
+}
 
-    let token_address = get_native_token(env);
-    let token_client = token::Client::new(env, &token_address);
-    let operator = get_operator(env);
+/// Grants access and stores the purchase record and stats. Moves no funds.
+fn record_purchase(
+    env: &Env,
+    buyer: &Address,
+    mut plan: PlanMeta,
+    token: &Address,
+    amount: i128,
+    referrer: &Option<Address>,
+) -> Result<PurchaseRecord, SynapseError> {
+    let plan_id = plan.id.clone();
+    // Refuse perpetual owners before moving any funds
+    grant_access(env, &plan_id, buyer, &get_license(env, &plan_id))?;
 
-    // Atomic: two transfers in one transaction
-    token_client.transfer(buyer, &plan.contributor, &contributor_share);
-    token_client.transfer(buyer, &operator, &operator_share);
+    let (contributor_share, operator_share) = split_amount(
+        amount,
+        get_contributor_share_bps(env)?,
+        &get_dust_recipient(env),
+    )?;
+    let payouts = contributor_payouts(env, &plan, contributor_share)?;
+    // The referrer's cut comes out of the operator share, rounded down
+    let (referral_share, operator_share) = match referrer {
+        Some(_) => split_amount(
+            operator_share,
+            get_referral_share_bps(env),
+            &DustRecipient::Operator,
+        )?,
+        None => (0, operator_share),
+    };
 
     let record = PurchaseRecord {
         buyer: buyer.clone(),
         amount_stroops: amount,
         contributor_share,
         operator_share,
+        payouts,
+        token: token.clone(),
+        referrer: referrer.clone(),
+        referral_share,
         ledger: env.ledger().sequence(),
     };
 
@@ -62,7 +186,130 @@ pub fn execute_purchase(
         .instance()
         .set(&DataKey::TotalPurchases, &(total + 1));
 
-    record
+    Ok(record)
+}
+
+/// Adds a record's payouts and operator share to the per-recipient totals.
+fn add_owed(owed: &mut Map<Address, i128>, record: &PurchaseRecord) -> Result<(), SynapseError> {
+    let env = owed.env().clone();
+    let mut shares = record.payouts.clone();
+    shares.push_back(Payout {
+        recipient: get_operator(&env)?,
+        amount: record.operator_share,
+    });
+    if let Some(referrer) = &record.referrer {
+        shares.push_back(Payout {
+            recipient: referrer.clone(),
+            amount: record.referral_share,
+        });
+    }
+    for share in shares.iter() {
+        let sum = owed
+            .get(share.recipient.clone())
+            .unwrap_or(0)
+            .checked_add(share.amount)
+            .ok_or(SynapseError::ArithmeticOverflow)?;
+        owed.set(share.recipient, sum);
+    }
+    Ok(())
+}
+
+/// Settles `total` according to the settlement mode: one transfer per
+/// recipient, or a single transfer into custody and claimable credits.
+pub fn pay_out(
+    env: &Env,
+    buyer: &Address,
+    token: &Address,
+    total: i128,
+    owed: &Map<Address, i128>,
+    in_custody: bool,
+) -> Result<(), SynapseError> {
+    let token_client = token::Client::new(env, token);
+    let payer = if in_custody {
+        env.current_contract_address()
+    } else {
+        buyer.clone()
+    };
+
+    match get_settlement_mode(env) {
+        SettlementMode::Push => {
+            // Atomic: all transfers in one transaction
+            for (recipient, amount) in owed.iter() {
+                if amount > 0 {
+                    token_client.transfer(&payer, &recipient, &amount);
+                }
+            }
+        }
+        SettlementMode::Accrue => {
+            // One transfer into custody; recipients claim later
+            if !in_custody {
+                token_client.transfer(buyer, &env.current_contract_address(), &total);
+            }
+            for (recipient, amount) in owed.iter() {
+                credit(env, &recipient, token, amount)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Divides a plan's contributor share between upstream royalties and the
+/// plan's own beneficiaries. Each ancestor passes the royalty slice of what
+/// it receives on to its own parent; the most distant one keeps all of it.
+pub fn contributor_payouts(
+    env: &Env,
+    plan: &PlanMeta,
+    contributor_share: i128,
+) -> Result<Vec<Payout>, SynapseError> {
+    let ancestors = get_ancestor_contributors(env, &plan.id);
+    let royalty_bps = get_royalty_bps(env);
+    let (mut upstream, own) = if ancestors.is_empty() {
+        (0, contributor_share)
+    } else {
+        split_amount(contributor_share, royalty_bps, &DustRecipient::Operator)?
+    };
+
+    let mut payouts = itemize_payouts(env, &get_beneficiaries(env, plan), own)?;
+    for (i, contributor) in ancestors.iter().enumerate() {
+        let (next, kept) = if i + 1 < ancestors.len() as usize {
+            split_amount(upstream, royalty_bps, &DustRecipient::Operator)?
+        } else {
+            (0, upstream)
+        };
+        if kept > 0 {
+            payouts.push_back(Payout {
+                recipient: contributor,
+                amount: kept,
+            });
+        }
+        upstream = next;
+    }
+    Ok(payouts)
+}
+
+/// Divides the contributor share by beneficiary weight, rounding each payout
+/// down. The leftover stroops go to the first beneficiary (the lead author),
+/// so the payouts always sum to `contributor_share`.
+fn itemize_payouts(
+    env: &Env,
+    beneficiaries: &Vec<Beneficiary>,
+    contributor_share: i128,
+) -> Result<Vec<Payout>, SynapseError> {
+    let mut payouts = Vec::new(env);
+    let mut remaining = contributor_share;
+    for b in beneficiaries.iter() {
+        let (amount, _) = split_amount(contributor_share, b.weight_bps, &DustRecipient::Operator)?;
+        remaining -= amount;
+        payouts.push_back(Payout {
+            recipient: b.address,
+            amount,
+        });
+    }
+    if let Some(mut lead) = payouts.get(0) {
+        lead.amount += remaining;
+        payouts.set(0, lead);
+    }
+    Ok(payouts)
 }
 
 pub fn get_purchases(env: &Env, plan_id: &BytesN<16>) -> Vec<PurchaseRecord> {
@@ -0,0 +1,126 @@
+use soroban_sdk::{Address, BytesN, Env, Map, Vec};
+
+use crate::admin::{get_native_token, get_operator};
+use crate::errors::SynapseError;
+use crate::plan::{load_plan, require_active, TTL_HOT};
+use crate::purchase::{contributor_payouts, pay_out};
+use crate::split::{split_amount, MAX_BPS};
+use crate::storage_keys::DataKey;
+use crate::types::{DustRecipient, SearchReceipt};
+
+/// Default search fee: 0.2 XLM, in stroops.
+pub const DEFAULT_SEARCH_FEE: i128 = 2_000_000;
+/// Most result plans a single search can credit.
+pub const MAX_SEARCH_RESULTS: u32 = 20;
+
+pub fn get_search_fee(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SearchFee)
+        .unwrap_or(DEFAULT_SEARCH_FEE)
+}
+
+/// Slice of each search fee shared with the returned plans' contributors.
+/// Defaults to none: the operator keeps the whole fee.
+pub fn get_search_share_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::SearchShareBps)
+        .unwrap_or(0)
+}
+
+pub fn set_search_fee(env: &Env, fee: i128, share_bps: u32) -> Result<(), SynapseError> {
+    if fee <= 0 {
+        return Err(SynapseError::InvalidAmount);
+    }
+    if share_bps > MAX_BPS {
+        return Err(SynapseError::InvalidShareBps);
+    }
+    env.storage().instance().set(&DataKey::SearchFee, &fee);
+    env.storage()
+        .instance()
+        .set(&DataKey::SearchShareBps, &share_bps);
+    Ok(())
+}
+
+pub fn get_receipt(env: &Env, id: u64) -> Option<SearchReceipt> {
+    env.storage().persistent().get(&DataKey::SearchReceipt(id))
+}
+
+/// Charges the search fee in the native token and records a receipt. The
+/// contributor slice is divided evenly between the result plans, which must
+/// be distinct and active, then by each plan's beneficiary weights;
+/// remainders go to the operator.
+pub fn pay_search(
+    env: &Env,
+    buyer: &Address,
+    query_hash: &BytesN<32>,
+    result_plan_ids: &Vec<BytesN<16>>,
+) -> Result<SearchReceipt, SynapseError> {
+    buyer.require_auth();
+    if result_plan_ids.len() > MAX_SEARCH_RESULTS {
+        return Err(SynapseError::TooManyResults);
+    }
+    for (i, plan_id) in result_plan_ids.iter().enumerate() {
+        if result_plan_ids
+            .iter()
+            .skip(i + 1)
+            .any(|other| other == plan_id)
+        {
+            return Err(SynapseError::DuplicatePlan);
+        }
+        load_plan(env, &plan_id)?;
+        require_active(env, &plan_id)?;
+    }
+
+    let fee = get_search_fee(env);
+    let token = get_native_token(env)?;
+    let (mut slice, _) = split_amount(fee, get_search_share_bps(env), &DustRecipient::Operator)?;
+    if result_plan_ids.is_empty() {
+        slice = 0;
+    }
+
+    let mut owed: Map<Address, i128> = Map::new(env);
+    let mut shared: i128 = 0;
+    if slice > 0 {
+        let per_plan = slice / result_plan_ids.len() as i128;
+        for plan_id in result_plan_ids.iter() {
+            let plan = load_plan(env, &plan_id)?;
+            for payout in contributor_payouts(env, &plan, per_plan)?.iter() {
+                let sum = owed.get(payout.recipient.clone()).unwrap_or(0) + payout.amount;
+                owed.set(payout.recipient, sum);
+            }
+            shared += per_plan;
+        }
+    }
+    let operator = get_operator(env)?;
+    let operator_sum = owed.get(operator.clone()).unwrap_or(0) + (fee - shared);
+    owed.set(operator, operator_sum);
+
+    pay_out(env, buyer, &token, fee, &owed, false)?;
+
+    let id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::SearchCount)
+        .unwrap_or(0)
+        + 1;
+    env.storage().instance().set(&DataKey::SearchCount, &id);
+
+    let receipt = SearchReceipt {
+        id,
+        buyer: buyer.clone(),
+        query_hash: query_hash.clone(),
+        result_plan_ids: result_plan_ids.clone(),
+        fee,
+        contributor_share: shared,
+        token,
+        ledger: env.ledger().sequence(),
+    };
+    let key = DataKey::SearchReceipt(id);
+    env.storage().persistent().set(&key, &receipt);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_HOT, TTL_HOT);
+    Ok(receipt)
+}
@@ -0,0 +1,47 @@
+use crate::errors::SynapseError;
+use crate::types::DustRecipient;
+
+/// 100% expressed in basis points.
+pub const MAX_BPS: u32 = 10_000;
+
+/// Splits `amount` into (contributor_share, operator_share) by `share_bps`.
+///
+/// The contributor's exact share is usually fractional. The whole-stroop part
+/// goes to the contributor and the leftover fraction (the dust) is rounded up
+/// in favour of `dust`: with `Contributor` the contributor share is rounded
+/// up, with `Operator` it is rounded down. The two shares always sum to
+/// `amount`, and the computation cannot overflow for any non-negative amount.
+pub fn split_amount(
+    amount: i128,
+    share_bps: u32,
+    dust: &DustRecipient,
+) -> Result<(i128, i128), SynapseError> {
+    if amount < 0 {
+        return Err(SynapseError::InvalidAmount);
+    }
+    if share_bps > MAX_BPS {
+        return Err(SynapseError::InvalidShareBps);
+    }
+
+    // amount * bps / 10_000, computed as (q * bps) + (r * bps / 10_000) so the
+    // intermediate product never exceeds i128 even for i128::MAX
+    let bps = share_bps as i128;
+    let denom = MAX_BPS as i128;
+    let (q, r) = (amount / denom, amount % denom);
+    let scaled_rem = r.checked_mul(bps).ok_or(SynapseError::ArithmeticOverflow)?;
+
+    let mut contributor_share = q
+        .checked_mul(bps)
+        .and_then(|whole| whole.checked_add(scaled_rem / denom))
+        .ok_or(SynapseError::ArithmeticOverflow)?;
+    if scaled_rem % denom != 0 && *dust == DustRecipient::Contributor {
+        contributor_share = contributor_share
+            .checked_add(1)
+            .ok_or(SynapseError::ArithmeticOverflow)?;
+    }
+
+    let operator_share = amount
+        .checked_sub(contributor_share)
+        .ok_or(SynapseError::ArithmeticOverflow)?;
+    Ok((contributor_share, operator_share))
+}
@@ -1,18 +1,104 @@
 use soroban_sdk::{contracttype, Address, BytesN};
 
+use crate::types::PausableOp;
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     // Instance storage
     Admin,
+    PendingAdmin,
     OperatorAddress,
+    /// Legacy whole-percent share (schema v1), replaced by ContributorShareBps
     ContributorSharePct,
+    ContributorShareBps,
+    DustRecipient,
+    ReferralShareBps,
+    RoyaltyBps,
     PlanCount,
     TotalPurchases,
     NativeTokenAddress,
+    MinPrice,
+    MaxPrice,
+    SchemaVersion,
+    Guardian,
+    Moderator,
+    Paused(PausableOp),
+    ShareChangeDelay,
+    PendingShareChange,
+    SettlementMode,
+    AcceptedTokens,
+    /// (floor, ceiling) for plan prices in a non-native token
+    TokenPriceBounds(Address),
+    Subscription,
+    SearchFee,
+    SearchShareBps,
+    SearchCount,
+    /// Accounting period length in ledgers, fixed once first configured
+    SubscriptionPeriod,
+    ChannelCount,
     // Persistent storage
     Plan(BytesN<16>),
     ContentHash(BytesN<32>),
     Purchases(BytesN<16>),
     ContribPlans(Address),
+    Beneficiaries(BytesN<16>),
+    /// (recipient, token)
+    Claimable(Address, Address),
+    /// (plan, token): price in a non-native token
+    PlanPrice(BytesN<16>, Address),
+    /// (buyer, token): prepaid balance held by the contract
+    BuyerBalance(Address, Address),
+    /// (plan, buyer): the buyer's `Entitlement`
+    Access(BytesN<16>, Address),
+    /// Plan's `PlanVersion` log, written on the first content update
+    Versions(BytesN<16>),
+    /// Plan a derivative extends
+    Parent(BytesN<16>),
+    /// Plans that extend this one
+    Derivatives(BytesN<16>),
+    /// Plan's `LicenseModel`; absent means perpetual
+    License(BytesN<16>),
+    SearchReceipt(u64),
+    /// Buyer's `SubscriptionPass`
+    Pass(Address),
+    /// (period, token): contributor income from passes awaiting distribution
+    SubPool(u32, Address),
+    /// Recalls made under passes in a period
+    SubRecalls(u32),
+    /// (period, plan): recalls of one plan made under passes
+    PlanRecalls(u32, BytesN<16>),
+    /// (period, token, plan): the plan's pool share has been credited
+    SubPaid(u32, Address, BytesN<16>),
+    /// (period, token): (amount credited, recalls covered) by distributions
+    SubSettled(u32, Address),
+    /// (period, plan, buyer): the buyer's recall of the plan has been counted
+    Recalled(u32, BytesN<16>, Address),
+    /// (buyer, token): payment channel redeemed with signed vouchers
+    Channel(Address, Address),
+}
+
+/// Storage keys that no longer fit in `DataKey`, which is at the contract
+/// type variant limit.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKeyExt {
+    // Instance storage
+    /// New plans start `Pending` until the admin approves them
+    PlanReview,
+    /// Buyer flags that quarantine a plan; zero disables quarantine
+    FlagThreshold,
+    // Persistent storage
+    /// Plan's `PlanStatus`; absent means active
+    Status(BytesN<16>),
+    /// Blocklisted content hash, mapped to the plan taken down for it
+    BlockedHash(BytesN<32>),
+    /// Plan's `Takedown` record
+    Takedown(BytesN<16>),
+    /// Plan's `PlanFlag`s, in filing order
+    Flags(BytesN<16>),
+    /// (plan, buyer): the buyer has flagged the plan
+    Flagged(BytesN<16>, Address),
+    /// Plan's flag count when the admin last activated it
+    FlagsReviewed(BytesN<16>),
 }
@@ -0,0 +1,239 @@
+use soroban_sdk::{token, Address, BytesN, Env, Vec};
+
+use crate::admin::{get_contributor_share_bps, get_dust_recipient, get_operator};
+use crate::earnings::credit;
+use crate::errors::SynapseError;
+use crate::moderation::get_takedown;
+use crate::plan::{load_plan, require_active, TTL_HOT};
+use crate::purchase::contributor_payouts;
+use crate::split::split_amount;
+use crate::storage_keys::DataKey;
+use crate::tokens::require_accepted;
+use crate::types::{PlanMeta, PurchaseRecord, SubscriptionPass, SubscriptionProduct};
+
+pub fn get_product(env: &Env) -> Option<SubscriptionProduct> {
+    env.storage().instance().get(&DataKey::Subscription)
+}
+
+/// Configures (or withdraws) the subscription product. The pass duration is
+/// also the accounting period for pooled income, so once set it cannot be
+/// changed.
+pub fn set_product(env: &Env, product: &Option<SubscriptionProduct>) -> Result<(), SynapseError> {
+    match product {
+        Some(product) => {
+            if product.price <= 0 || product.duration == 0 {
+                return Err(SynapseError::InvalidSubscription);
+            }
+            require_accepted(env, &product.token)?;
+            match get_period_length(env) {
+                Some(length) if length != product.duration => {
+                    return Err(SynapseError::InvalidSubscription)
+                }
+                Some(_) => {}
+                None => env
+                    .storage()
+                    .instance()
+                    .set(&DataKey::SubscriptionPeriod, &product.duration),
+            }
+            env.storage()
+                .instance()
+                .set(&DataKey::Subscription, product);
+        }
+        None => env.storage().instance().remove(&DataKey::Subscription),
+    }
+    Ok(())
+}
+
+fn get_period_length(env: &Env) -> Option<u32> {
+    env.storage().instance().get(&DataKey::SubscriptionPeriod)
+}
+
+/// Index of the accounting period the current ledger falls in.
+pub fn current_period(env: &Env) -> Result<u32, SynapseError> {
+    let length = get_period_length(env).ok_or(SynapseError::SubscriptionNotConfigured)?;
+    Ok(env.ledger().sequence() / length)
+}
+
+pub fn get_pass(env: &Env, buyer: &Address) -> Option<SubscriptionPass> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Pass(buyer.clone()))
+}
+
+/// The buyer's pass, if it has not expired.
+pub fn get_active_pass(env: &Env, buyer: &Address) -> Option<SubscriptionPass> {
+    get_pass(env, buyer).filter(|pass| env.ledger().sequence() < pass.expires_at_ledger)
+}
+
+/// Contributor income pooled for `period`, before distribution.
+pub fn get_pool(env: &Env, period: u32, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SubPool(period, token.clone()))
+        .unwrap_or(0)
+}
+
+/// Recalls made under passes during `period`, in total or for one plan.
+pub fn get_recalls(env: &Env, period: u32, plan_id: &Option<BytesN<16>>) -> u32 {
+    let key = match plan_id {
+        Some(plan_id) => DataKey::PlanRecalls(period, plan_id.clone()),
+        None => DataKey::SubRecalls(period),
+    };
+    env.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn set_persistent<V: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(
+    env: &Env,
+    key: &DataKey,
+    value: &V,
+) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(key, TTL_HOT, TTL_HOT);
+}
+
+/// Sells the buyer a pass, extending any active one. The operator's share is
+/// credited at once; the rest is pooled for the current period. Returns the
+/// updated pass.
+pub fn buy_pass(env: &Env, buyer: &Address) -> Result<SubscriptionPass, SynapseError> {
+    buyer.require_auth();
+    let product = get_product(env).ok_or(SynapseError::SubscriptionNotConfigured)?;
+    let period = current_period(env)?;
+
+    let (contributor_share, operator_share) = split_amount(
+        product.price,
+        get_contributor_share_bps(env)?,
+        &get_dust_recipient(env),
+    )?;
+
+    token::Client::new(env, &product.token).transfer(
+        buyer,
+        &env.current_contract_address(),
+        &product.price,
+    );
+    credit(env, &get_operator(env)?, &product.token, operator_share)?;
+    let pool = get_pool(env, period, &product.token)
+        .checked_add(contributor_share)
+        .ok_or(SynapseError::ArithmeticOverflow)?;
+    set_persistent(env, &DataKey::SubPool(period, product.token.clone()), &pool);
+
+    let now = env.ledger().sequence();
+    let from = match get_pass(env, buyer) {
+        Some(pass) if pass.expires_at_ledger > now => pass.expires_at_ledger,
+        _ => now,
+    };
+    let pass = SubscriptionPass {
+        expires_at_ledger: from
+            .checked_add(product.duration)
+            .ok_or(SynapseError::ArithmeticOverflow)?,
+        token: product.token,
+    };
+    set_persistent(env, &DataKey::Pass(buyer.clone()), &pass);
+    Ok(pass)
+}
+
+/// Counts a recall of `plan` by a pass holder toward this period's
+/// distribution, at most once per buyer and plan in a period, so repeat
+/// recalls cannot inflate a plan's share. Nothing is charged.
+pub fn record_recall(
+    env: &Env,
+    buyer: &Address,
+    plan: &PlanMeta,
+    pass: &SubscriptionPass,
+) -> Result<PurchaseRecord, SynapseError> {
+    require_active(env, &plan.id)?;
+    let period = current_period(env)?;
+
+    let recalled_key = DataKey::Recalled(period, plan.id.clone(), buyer.clone());
+    if !env.storage().persistent().has(&recalled_key) {
+        env.storage().persistent().set(&recalled_key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(&recalled_key, TTL_HOT, TTL_HOT);
+
+        let total = get_recalls(env, period, &None) + 1;
+        set_persistent(env, &DataKey::SubRecalls(period), &total);
+        let plan_id = Some(plan.id.clone());
+        let count = get_recalls(env, period, &plan_id) + 1;
+        set_persistent(env, &DataKey::PlanRecalls(period, plan.id.clone()), &count);
+    }
+
+    Ok(PurchaseRecord {
+        buyer: buyer.clone(),
+        amount_stroops: 0,
+        contributor_share: 0,
+        operator_share: 0,
+        payouts: Vec::new(env),
+        token: pass.token.clone(),
+        referrer: None,
+        referral_share: 0,
+        ledger: env.ledger().sequence(),
+    })
+}
+
+/// Credits each plan's beneficiaries with the plan's share of a finished
+/// period's pool, in proportion to its recalls. Plans already paid for the
+/// period are skipped, and plans taken down by moderation forfeit their
+/// share. Shares are rounded down; once every recalled plan has been paid,
+/// whatever is left over goes to the operator, as does the whole pool if
+/// nothing was recalled. Returns the amount credited.
+pub fn distribute(
+    env: &Env,
+    period: u32,
+    token: &Address,
+    plan_ids: &Vec<BytesN<16>>,
+) -> Result<i128, SynapseError> {
+    if period >= current_period(env)? {
+        return Err(SynapseError::PeriodNotEnded);
+    }
+    let pool = get_pool(env, period, token);
+    let total = get_recalls(env, period, &None);
+    if total == 0 {
+        credit(env, &get_operator(env)?, token, pool)?;
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SubPool(period, token.clone()));
+        return Ok(pool);
+    }
+
+    let settled_key = DataKey::SubSettled(period, token.clone());
+    let (mut settled, mut covered): (i128, u32) = env
+        .storage()
+        .persistent()
+        .get(&settled_key)
+        .unwrap_or((0, 0));
+    let mut distributed: i128 = 0;
+    for plan_id in plan_ids.iter() {
+        let paid_key = DataKey::SubPaid(period, token.clone(), plan_id.clone());
+        if env.storage().persistent().has(&paid_key) {
+            continue;
+        }
+        let recalls = get_recalls(env, period, &Some(plan_id.clone()));
+        if recalls == 0 {
+            continue;
+        }
+        set_persistent(env, &paid_key, &true);
+        covered += recalls;
+        if get_takedown(env, &plan_id).is_some() {
+            continue;
+        }
+        let share = pool
+            .checked_mul(recalls as i128)
+            .ok_or(SynapseError::ArithmeticOverflow)?
+            / total as i128;
+        let plan = load_plan(env, &plan_id)?;
+        for payout in contributor_payouts(env, &plan, share)?.iter() {
+            credit(env, &payout.recipient, token, payout.amount)?;
+        }
+        distributed += share;
+    }
+
+    settled += distributed;
+    if covered == total && settled < pool {
+        let remainder = pool - settled;
+        credit(env, &get_operator(env)?, token, remainder)?;
+        settled = pool;
+        distributed += remainder;
+    }
+    set_persistent(env, &settled_key, &(settled, covered));
+    Ok(distributed)
+}
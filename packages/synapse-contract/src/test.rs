@@ -1,9 +1,18 @@
+extern crate std;
+
 use soroban_sdk::{
     testutils::{Address as _, Ledger, LedgerInfo},
-    Address, BytesN, Env, String, Vec,
+    Address, Bytes, BytesN, Env, String, Vec,
 };
 
-use crate::types::StorePlanInput;
+use crate::errors::SynapseError;
+use crate::migration::{PlanMetaV1, PurchaseRecordV1, SCHEMA_VERSION};
+use crate::split::{split_amount, MAX_BPS};
+use crate::storage_keys::DataKey;
+use crate::types::{
+    AppealStatus, Beneficiary, DustRecipient, Entitlement, LicenseModel, PausableOp, Payout,
+    PlanMetaUpdate, PlanStatus, SettlementMode, StorePlanInput, SubscriptionProduct, Voucher,
+};
 use crate::{SynapseContract, SynapseContractClient};
 
 fn setup_env() -> (Env, SynapseContractClient<'static>, Address, Address, Address) {
@@ -17,11 +26,18 @@ fn setup_env() -> (Env, SynapseContractClient<'static>, Address, Address, Addres
     let operator = Address::generate(&env);
     let native_token = env.register_stellar_asset_contract_v2(admin.clone()).address();
 
-    client.initialize(&admin, &operator, &70u32, &native_token);
+    client.initialize(&admin, &operator, &7000u32, &native_token);
 
     (env, client, admin, operator, native_token)
 }
 
+fn sign_voucher(env: &Env, key: &ed25519_dalek::SigningKey, voucher: &Voucher) -> BytesN<64> {
+    use ed25519_dalek::Signer;
+    use soroban_sdk::xdr::ToXdr;
+    let msg: std::vec::Vec<u8> = voucher.clone().to_xdr(env).iter().collect();
+    BytesN::from_array(env, &key.sign(&msg).to_bytes())
+}
+
 fn make_plan_id(env: &Env, val: u8) -> BytesN<16> {
     let mut bytes = [0u8; 16];
     bytes[0] = val;
@@ -36,7 +52,7 @@ fn make_content_hash(env: &Env, val: u8) -> BytesN<32> {
 
 fn make_input(env: &Env, id: BytesN<16>, hash: BytesN<32>, title: &str, score: u32) -> StorePlanInput {
     StorePlanInput {
-        id,
+        id: Some(id),
         title: String::from_str(env, title),
         description: String::from_str(env, "Description"),
         content_hash: hash,
@@ -46,6 +62,10 @@ fn make_input(env: &Env, id: BytesN<16>, hash: BytesN<32>, title: &str, score: u
         language: String::from_str(env, ""),
         framework: String::from_str(env, ""),
         quality_score: score,
+        price: 10_000_000,
+        beneficiaries: Vec::new(env),
+        license: LicenseModel::Perpetual,
+        parent: None,
     }
 }
 
@@ -56,17 +76,32 @@ fn test_initialize() {
     let stats = client.get_stats();
     assert_eq!(stats.total_plans, 0);
     assert_eq!(stats.total_purchases, 0);
-    assert_eq!(client.get_share_pct(), 70);
+    assert_eq!(client.get_share_bps(), 7000);
 }
 
 #[test]
-#[should_panic(expected = "Already initialized")]
 fn test_double_initialize() {
     let (env, client, _admin, _operator, _native_token) = setup_env();
     let admin2 = Address::generate(&env);
     let op2 = Address::generate(&env);
     let tok2 = Address::generate(&env);
-    client.initialize(&admin2, &op2, &50u32, &tok2);
+    let result = client.try_initialize(&admin2, &op2, &5000u32, &tok2);
+    assert_eq!(result, Err(Ok(SynapseError::AlreadyInitialized)));
+}
+
+#[test]
+fn test_initialize_invalid_share() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(SynapseContract, ());
+    let client = SynapseContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let token = Address::generate(&env);
+    let result = client.try_initialize(&admin, &operator, &10_001u32, &token);
+    assert_eq!(result, Err(Ok(SynapseError::InvalidShareBps)));
 }
 
 #[test]
@@ -88,7 +123,7 @@ fn test_store_and_get_plan() {
     });
 
     let input = StorePlanInput {
-        id: plan_id.clone(),
+        id: Some(plan_id.clone()),
         title: String::from_str(&env, "Test Plan"),
         description: String::from_str(&env, "A test plan description"),
         content_hash,
@@ -98,12 +133,18 @@ fn test_store_and_get_plan() {
         language: String::from_str(&env, "rust"),
         framework: String::from_str(&env, "soroban-sdk"),
         quality_score: 85,
+        price: 5_000_000,
+        beneficiaries: Vec::new(&env),
+        license: LicenseModel::Perpetual,
+        parent: None,
     };
 
-    client.store_plan(&contributor, &input);
+    let stored_id = client.store_plan(&contributor, &input);
+    assert_eq!(stored_id, plan_id);
 
     let plan = client.get_plan(&plan_id).unwrap();
     assert_eq!(plan.quality_score, 85);
+    assert_eq!(plan.price, 5_000_000);
     assert_eq!(plan.purchase_count, 0);
     assert_eq!(plan.created_at, 1700000000);
 
@@ -127,7 +168,6 @@ fn test_content_exists() {
 }
 
 #[test]
-#[should_panic(expected = "Content with this hash already exists")]
 fn test_duplicate_content() {
     let (env, client, _admin, _operator, _native_token) = setup_env();
     let contributor = Address::generate(&env);
@@ -137,7 +177,8 @@ fn test_duplicate_content() {
     let input2 = make_input(&env, make_plan_id(&env, 4), content_hash, "Plan B", 50);
 
     client.store_plan(&contributor, &input1);
-    client.store_plan(&contributor, &input2);
+    let result = client.try_store_plan(&contributor, &input2);
+    assert_eq!(result, Err(Ok(SynapseError::ContentExists)));
 }
 
 #[test]
@@ -169,7 +210,7 @@ fn test_purchase_plan() {
     let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 20), "Buyable", 70);
     client.store_plan(&contributor, &input);
 
-    let record = client.purchase_plan(&buyer, &plan_id, &10_000_000_i128);
+    let record = client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
     assert_eq!(record.contributor_share, 7_000_000);
     assert_eq!(record.operator_share, 3_000_000);
 
@@ -206,3 +247,1965 @@ fn test_set_tier() {
     let plan = client.get_plan(&plan_id).unwrap();
     assert_eq!(plan.tier, crate::types::StorageTier::Cold);
 }
+
+#[test]
+fn test_purchase_missing_plan() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let buyer = Address::generate(&env);
+
+    let plan_id = make_plan_id(&env, 40);
+    let result = client.try_purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::PlanNotFound));
+}
+
+#[test]
+fn test_set_tier_missing_plan() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let plan_id = make_plan_id(&env, 41);
+
+    let result = client.try_set_tier(&admin, &plan_id, &crate::types::StorageTier::Cold);
+    assert_eq!(result, Err(Ok(SynapseError::PlanNotFound)));
+    assert_eq!(client.try_bump_plan_ttl(&plan_id), Err(Ok(SynapseError::PlanNotFound)));
+}
+
+#[test]
+fn test_set_tier_unauthorized() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 42);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 42), "Guarded", 60);
+    client.store_plan(&contributor, &input);
+
+    let result = client.try_set_tier(&stranger, &plan_id, &crate::types::StorageTier::Cold);
+    assert_eq!(result, Err(Ok(SynapseError::Unauthorized)));
+}
+
+#[test]
+fn test_purchase_underpayment() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &10_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 50);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 50), "Priced", 70);
+    client.store_plan(&contributor, &input);
+
+    for amount in [0_i128, -1, 9_999_999] {
+        let result = client.try_purchase_plan(&buyer, &plan_id, &native_token, &amount, &None);
+        assert_eq!(result.unwrap_err(), Ok(SynapseError::Underpayment));
+    }
+
+    let plan = client.get_plan(&plan_id).unwrap();
+    assert_eq!(plan.purchase_count, 0);
+    assert_eq!(client.get_purchases(&plan_id).len(), 0);
+}
+
+#[test]
+fn test_purchase_charges_listed_price() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &50_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 51);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 51), "Priced", 70);
+    client.store_plan(&contributor, &input);
+
+    // Offering more than the price only charges the price
+    let record = client.purchase_plan(&buyer, &plan_id, &native_token, &50_000_000_i128, &None);
+    assert_eq!(record.amount_stroops, 10_000_000);
+
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+    assert_eq!(token_client.balance(&buyer), 40_000_000);
+}
+
+#[test]
+fn test_store_plan_price_bounds() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+
+    client.set_price_bounds(&1_000_000_i128, &20_000_000_i128);
+    assert_eq!(client.get_price_bounds(), (1_000_000, 20_000_000));
+
+    let mut input = make_input(&env, make_plan_id(&env, 52), make_content_hash(&env, 52), "Cheap", 50);
+    input.price = 999_999;
+    assert_eq!(
+        client.try_store_plan(&contributor, &input),
+        Err(Ok(SynapseError::PriceOutOfBounds))
+    );
+
+    input.price = 20_000_001;
+    assert_eq!(
+        client.try_store_plan(&contributor, &input),
+        Err(Ok(SynapseError::PriceOutOfBounds))
+    );
+
+    input.price = 20_000_000;
+    client.store_plan(&contributor, &input);
+}
+
+#[test]
+fn test_set_price_bounds_invalid() {
+    let (_env, client, _admin, _operator, _native_token) = setup_env();
+
+    assert_eq!(
+        client.try_set_price_bounds(&0_i128, &10_i128),
+        Err(Ok(SynapseError::InvalidPriceBounds))
+    );
+    assert_eq!(
+        client.try_set_price_bounds(&10_i128, &9_i128),
+        Err(Ok(SynapseError::InvalidPriceBounds))
+    );
+}
+
+#[test]
+fn test_set_price() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 53);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 53), "Repriced", 60);
+    client.store_plan(&contributor, &input);
+
+    client.set_price(&contributor, &plan_id, &native_token, &20_000_000_i128);
+    assert_eq!(client.get_plan(&plan_id).unwrap().price, 20_000_000);
+
+    assert_eq!(
+        client.try_set_price(&stranger, &plan_id, &native_token, &1_i128),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+    assert_eq!(
+        client.try_set_price(&contributor, &plan_id, &native_token, &0_i128),
+        Err(Ok(SynapseError::PriceOutOfBounds))
+    );
+}
+
+#[test]
+fn test_plan_id_collision() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let owner = Address::generate(&env);
+    let hijacker = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 60);
+
+    let input1 = make_input(&env, plan_id.clone(), make_content_hash(&env, 60), "Original", 50);
+    let input2 = make_input(&env, plan_id.clone(), make_content_hash(&env, 61), "Hijack", 50);
+
+    client.store_plan(&owner, &input1);
+    let result = client.try_store_plan(&hijacker, &input2);
+    assert_eq!(result, Err(Ok(SynapseError::PlanIdExists)));
+
+    let plan = client.get_plan(&plan_id).unwrap();
+    assert_eq!(plan.contributor, owner);
+    assert_eq!(plan.title, String::from_str(&env, "Original"));
+    assert!(!client.content_exists(&make_content_hash(&env, 61)));
+}
+
+#[test]
+fn test_derived_plan_id() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let other = Address::generate(&env);
+    let content_hash = make_content_hash(&env, 62);
+
+    let mut input = make_input(&env, make_plan_id(&env, 0), content_hash.clone(), "Derived", 50);
+    input.id = None;
+
+    let expected = client.derive_plan_id(&contributor, &content_hash);
+    assert_ne!(expected, client.derive_plan_id(&other, &content_hash));
+    assert_ne!(expected, client.derive_plan_id(&contributor, &make_content_hash(&env, 63)));
+
+    let plan_id = client.store_plan(&contributor, &input);
+    assert_eq!(plan_id, expected);
+    assert_eq!(client.get_plan(&plan_id).unwrap().content_hash, content_hash);
+    assert_eq!(client.get_contributor_plans(&contributor).get(0).unwrap(), plan_id);
+}
+
+#[test]
+fn test_admin_transfer() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let new_admin = Address::generate(&env);
+
+    assert_eq!(client.get_pending_admin(), None);
+    client.propose_admin(&new_admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    // Proposal alone does not hand over control
+    assert_eq!(client.get_admin(), admin);
+
+    client.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    // New admin can now use admin-only entrypoints
+    client.set_operator(&Address::generate(&env));
+    assert_eq!(env.auths()[0].0, new_admin);
+}
+
+#[test]
+fn test_cancel_admin_transfer() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let new_admin = Address::generate(&env);
+
+    assert_eq!(client.try_accept_admin(), Err(Ok(SynapseError::NoPendingAdmin)));
+    assert_eq!(
+        client.try_cancel_admin_transfer(),
+        Err(Ok(SynapseError::NoPendingAdmin))
+    );
+
+    client.propose_admin(&new_admin);
+    client.cancel_admin_transfer();
+    assert_eq!(client.get_pending_admin(), None);
+    assert_eq!(client.try_accept_admin(), Err(Ok(SynapseError::NoPendingAdmin)));
+    assert_eq!(client.get_admin(), admin);
+}
+
+/// Smallest module the host accepts as a contract: just the env meta section
+/// declaring protocol 22.
+fn minimal_wasm(env: &Env) -> Bytes {
+    let mut wasm = [0u8; 40];
+    wasm[..8].copy_from_slice(b"\0asm\x01\0\0\0");
+    wasm[8] = 0; // custom section
+    wasm[9] = 30; // section size
+    wasm[10] = 17; // name length
+    wasm[11..28].copy_from_slice(b"contractenvmetav0");
+    // ScEnvMetaEntry::InterfaceVersion { protocol: 22, pre_release: 0 }
+    wasm[35] = 22;
+    Bytes::from_slice(env, &wasm)
+}
+
+#[test]
+fn test_upgrade() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 70);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 70), "Kept", 50);
+    client.store_plan(&contributor, &input);
+
+    let wasm_hash = env.deployer().upload_contract_wasm(minimal_wasm(&env));
+    client.upgrade(&wasm_hash);
+    assert_eq!(env.auths()[0].0, admin);
+
+    // The new code has none of the old entrypoints...
+    assert!(client.try_get_stats().is_err());
+    // ...but storage survives the upgrade
+    env.as_contract(&client.address, || {
+        let plan: crate::types::PlanMeta = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Plan(plan_id.clone()))
+            .unwrap();
+        assert_eq!(plan.contributor, contributor);
+        let stored_admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        assert_eq!(stored_admin, admin);
+    });
+}
+
+#[test]
+fn test_migrate_v1_plans() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let legacy_id = make_plan_id(&env, 71);
+    let legacy_buyer = Address::generate(&env);
+    let current_id = make_plan_id(&env, 72);
+
+    let input = make_input(&env, current_id.clone(), make_content_hash(&env, 72), "Current", 50);
+    client.store_plan(&contributor, &input);
+
+    // Simulate a deployment of the original contract: a v1 plan entry and
+    // no schema version key
+    env.as_contract(&client.address, || {
+        let legacy = PlanMetaV1 {
+            id: legacy_id.clone(),
+            title: String::from_str(&env, "Legacy"),
+            description: String::from_str(&env, "Stored before pricing"),
+            content_hash: make_content_hash(&env, 71),
+            ipfs_cid: String::from_str(&env, "QmLegacy"),
+            tags: Vec::new(&env),
+            domain: String::from_str(&env, ""),
+            language: String::from_str(&env, ""),
+            framework: String::from_str(&env, ""),
+            contributor: contributor.clone(),
+            quality_score: 40,
+            purchase_count: 3,
+            tier: crate::types::StorageTier::Cold,
+            created_at: 1_600_000_000,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Plan(legacy_id.clone()), &legacy);
+        let legacy_purchase = PurchaseRecordV1 {
+            buyer: legacy_buyer.clone(),
+            amount_stroops: 10_000_000,
+            contributor_share: 7_000_000,
+            operator_share: 3_000_000,
+            ledger: 50,
+        };
+        env.storage().persistent().set(
+            &DataKey::Purchases(legacy_id.clone()),
+            &Vec::from_array(&env, [legacy_purchase]),
+        );
+        env.storage().instance().remove(&DataKey::SchemaVersion);
+        env.storage().instance().remove(&DataKey::ContributorShareBps);
+        env.storage()
+            .instance()
+            .set(&DataKey::ContributorSharePct, &70u32);
+    });
+    assert_eq!(client.get_schema_version(), 1);
+
+    let ids = Vec::from_array(&env, [legacy_id.clone(), current_id.clone()]);
+    // The legacy plan entry and its purchase list
+    assert_eq!(client.migrate(&ids), 2);
+    assert_eq!(client.get_schema_version(), SCHEMA_VERSION);
+    assert_eq!(client.get_share_bps(), 7000);
+
+    let plan = client.get_plan(&legacy_id).unwrap();
+    assert_eq!(plan.title, String::from_str(&env, "Legacy"));
+    assert_eq!(plan.purchase_count, 3);
+    assert_eq!(plan.tier, crate::types::StorageTier::Cold);
+    assert_eq!(plan.price, 10_000_000);
+    assert_eq!(client.get_plan(&current_id).unwrap().price, 10_000_000);
+
+    let purchases = client.get_purchases(&legacy_id);
+    assert_eq!(purchases.len(), 1);
+    let record = purchases.get(0).unwrap();
+    assert_eq!(record.ledger, 50);
+    assert_eq!(record.token, native_token);
+    assert_eq!((record.referrer, record.referral_share), (None, 0));
+    assert!(client.has_access(&legacy_buyer, &legacy_id));
+    assert_eq!(
+        record.payouts,
+        Vec::from_array(&env, [Payout { recipient: contributor.clone(), amount: 7_000_000 }])
+    );
+
+    // Re-running is a no-op
+    assert_eq!(client.migrate(&ids), 0);
+}
+
+#[test]
+fn test_migrate_missing_plan() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let ids = Vec::from_array(&env, [make_plan_id(&env, 73)]);
+    assert_eq!(client.try_migrate(&ids), Err(Ok(SynapseError::PlanNotFound)));
+}
+
+#[test]
+fn test_pause_store_plan() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let input = make_input(&env, make_plan_id(&env, 80), make_content_hash(&env, 80), "Paused", 50);
+
+    client.pause(&admin, &PausableOp::StorePlan);
+    assert!(client.is_paused(&PausableOp::StorePlan));
+    assert_eq!(client.try_store_plan(&contributor, &input), Err(Ok(SynapseError::Paused)));
+
+    client.unpause(&PausableOp::StorePlan);
+    client.store_plan(&contributor, &input);
+}
+
+#[test]
+fn test_pause_purchase_plan() {
+    let (env, client, admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &10_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 81);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 81), "Paused", 50);
+    client.store_plan(&contributor, &input);
+
+    client.pause(&admin, &PausableOp::PurchasePlan);
+    let result = client.try_purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::Paused));
+
+    // Other operations and getters are unaffected
+    assert!(!client.is_paused(&PausableOp::StorePlan));
+    client.set_tier(&contributor, &plan_id, &crate::types::StorageTier::Cold);
+    assert_eq!(client.get_plan(&plan_id).unwrap().purchase_count, 0);
+    assert_eq!(client.get_purchases(&plan_id).len(), 0);
+    assert_eq!(client.get_stats().total_plans, 1);
+
+    client.unpause(&PausableOp::PurchasePlan);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+}
+
+#[test]
+fn test_pause_set_tier() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 82);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 82), "Paused", 50);
+    client.store_plan(&contributor, &input);
+
+    client.pause(&admin, &PausableOp::SetTier);
+    let result = client.try_set_tier(&contributor, &plan_id, &crate::types::StorageTier::Cold);
+    assert_eq!(result, Err(Ok(SynapseError::Paused)));
+    assert_eq!(client.get_plan(&plan_id).unwrap().tier, crate::types::StorageTier::Hot);
+}
+
+#[test]
+fn test_guardian_pause() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let guardian = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    assert_eq!(
+        client.try_pause(&guardian, &PausableOp::PurchasePlan),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+
+    client.set_guardian(&Some(guardian.clone()));
+    assert_eq!(client.get_guardian(), Some(guardian.clone()));
+    client.pause(&guardian, &PausableOp::PurchasePlan);
+    assert!(client.is_paused(&PausableOp::PurchasePlan));
+
+    assert_eq!(
+        client.try_pause(&stranger, &PausableOp::StorePlan),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+
+    client.set_guardian(&None);
+    assert_eq!(client.get_guardian(), None);
+    assert_eq!(
+        client.try_pause(&guardian, &PausableOp::StorePlan),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_share_change_timelock() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let delay = client.get_share_change_delay();
+
+    let change = client.propose_share_change(&5000u32);
+    assert_eq!(change.new_share_bps, 5000);
+    assert_eq!(change.effective_ledger, env.ledger().sequence() + delay);
+    assert_eq!(client.get_pending_share_change(), Some(change.clone()));
+    assert_eq!(
+        client.try_propose_share_change(&6000u32),
+        Err(Ok(SynapseError::ShareChangePending))
+    );
+
+    env.ledger().with_mut(|li| li.sequence_number += delay - 1);
+    assert_eq!(client.try_execute_share_change(), Err(Ok(SynapseError::TimelockActive)));
+    assert_eq!(client.get_share_bps(), 7000);
+
+    env.ledger().with_mut(|li| li.sequence_number += 1);
+    client.execute_share_change();
+    assert_eq!(client.get_share_bps(), 5000);
+    assert_eq!(client.get_pending_share_change(), None);
+}
+
+#[test]
+fn test_cancel_share_change() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+
+    assert_eq!(
+        client.try_cancel_share_change(),
+        Err(Ok(SynapseError::NoPendingShareChange))
+    );
+    assert_eq!(
+        client.try_propose_share_change(&10_001u32),
+        Err(Ok(SynapseError::InvalidShareBps))
+    );
+
+    client.propose_share_change(&4000u32);
+    client.cancel_share_change();
+    assert_eq!(client.get_pending_share_change(), None);
+
+    env.ledger().with_mut(|li| li.sequence_number += client.get_share_change_delay());
+    assert_eq!(
+        client.try_execute_share_change(),
+        Err(Ok(SynapseError::NoPendingShareChange))
+    );
+    assert_eq!(client.get_share_bps(), 7000);
+}
+
+#[test]
+fn test_share_change_delay() {
+    let (_env, client, _admin, _operator, _native_token) = setup_env();
+
+    assert_eq!(
+        client.try_set_share_change_delay(&(crate::governance::MIN_SHARE_CHANGE_DELAY - 1)),
+        Err(Ok(SynapseError::InvalidDelay))
+    );
+    client.set_share_change_delay(&100_000u32);
+    assert_eq!(client.get_share_change_delay(), 100_000);
+
+    let change = client.propose_share_change(&6000u32);
+    assert_eq!(change.effective_ledger - change.proposed_at_ledger, 100_000);
+}
+
+#[test]
+fn test_split_amount_conserves_total() {
+    // Deterministic pseudo-random sweep (xorshift) plus hand-picked edges
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut amounts = [0_i128, 1, 2, 9_999, 10_000, 10_001, 10_000_000, i128::MAX, i128::MAX - 1]
+        .to_vec();
+    for _ in 0..200 {
+        amounts.push((next() as i128) << (next() % 63));
+    }
+    let mut shares = [0_u32, 1, 3333, 5000, 6667, 7000, 9999, MAX_BPS].to_vec();
+    for _ in 0..20 {
+        shares.push((next() % (MAX_BPS as u64 + 1)) as u32);
+    }
+
+    for amount in amounts.iter() {
+        for bps in shares.iter() {
+            let (c_down, o_down) = split_amount(*amount, *bps, &DustRecipient::Operator).unwrap();
+            let (c_up, o_up) = split_amount(*amount, *bps, &DustRecipient::Contributor).unwrap();
+            assert_eq!(c_down + o_down, *amount);
+            assert_eq!(c_up + o_up, *amount);
+            assert!(c_down >= 0 && o_down >= 0 && c_up >= 0 && o_up >= 0);
+            // Rounding moves at most one stroop of dust
+            assert!(c_up - c_down == 0 || c_up - c_down == 1);
+        }
+    }
+}
+
+#[test]
+fn test_split_amount_rounding() {
+    assert_eq!(split_amount(10_000_000, 7000, &DustRecipient::Operator), Ok((7_000_000, 3_000_000)));
+    assert_eq!(split_amount(3, 5000, &DustRecipient::Operator), Ok((1, 2)));
+    assert_eq!(split_amount(3, 5000, &DustRecipient::Contributor), Ok((2, 1)));
+    assert_eq!(split_amount(1, 1, &DustRecipient::Contributor), Ok((1, 0)));
+    assert_eq!(split_amount(1, 1, &DustRecipient::Operator), Ok((0, 1)));
+    assert_eq!(split_amount(-1, 7000, &DustRecipient::Operator), Err(SynapseError::InvalidAmount));
+    assert_eq!(
+        split_amount(1, MAX_BPS + 1, &DustRecipient::Operator),
+        Err(SynapseError::InvalidShareBps)
+    );
+}
+
+#[test]
+fn test_purchase_dust_recipient() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &20_i128);
+
+    client.set_price_bounds(&1_i128, &100_i128);
+    assert_eq!(client.get_dust_recipient(), DustRecipient::Operator);
+
+    let plan_id = make_plan_id(&env, 90);
+    let mut input = make_input(&env, plan_id.clone(), make_content_hash(&env, 90), "Odd", 50);
+    input.price = 9; // 70% of 9 = 6.3 stroops
+    client.store_plan(&contributor, &input);
+
+    let record = client.purchase_plan(&buyer, &plan_id, &native_token, &9_i128, &None);
+    assert_eq!((record.contributor_share, record.operator_share), (6, 3));
+
+    client.set_dust_recipient(&DustRecipient::Contributor);
+    let second_buyer = Address::generate(&env);
+    token_admin_client.mint(&second_buyer, &9_i128);
+    let record = client.purchase_plan(&second_buyer, &plan_id, &native_token, &9_i128, &None);
+    assert_eq!((record.contributor_share, record.operator_share), (7, 2));
+
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+    assert_eq!(token_client.balance(&contributor), 13);
+    assert_eq!(token_client.balance(&operator), 5);
+}
+
+fn beneficiary(address: &Address, weight_bps: u32) -> Beneficiary {
+    Beneficiary {
+        address: address.clone(),
+        weight_bps,
+    }
+}
+
+#[test]
+fn test_co_authored_purchase() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let lead = Address::generate(&env);
+    let co_author = Address::generate(&env);
+    let agent = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &10_000_003_i128);
+
+    let plan_id = make_plan_id(&env, 100);
+    let mut input = make_input(&env, plan_id.clone(), make_content_hash(&env, 100), "Team", 80);
+    input.price = 10_000_003;
+    input.beneficiaries = Vec::from_array(
+        &env,
+        [beneficiary(&lead, 5000), beneficiary(&co_author, 3333), beneficiary(&agent, 1667)],
+    );
+    client.store_plan(&lead, &input);
+    assert_eq!(client.get_beneficiaries(&plan_id), input.beneficiaries);
+
+    let record = client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_003_i128, &None);
+    assert_eq!(record.contributor_share, 7_000_002);
+    assert_eq!(record.operator_share, 3_000_001);
+    // 3333 and 1667 bps of 7_000_002 round down; the lead author keeps the dust
+    assert_eq!(
+        record.payouts,
+        Vec::from_array(
+            &env,
+            [
+                Payout { recipient: lead.clone(), amount: 3_500_002 },
+                Payout { recipient: co_author.clone(), amount: 2_333_100 },
+                Payout { recipient: agent.clone(), amount: 1_166_900 },
+            ]
+        )
+    );
+
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+    assert_eq!(token_client.balance(&lead), 3_500_002);
+    assert_eq!(token_client.balance(&co_author), 2_333_100);
+    assert_eq!(token_client.balance(&agent), 1_166_900);
+    assert_eq!(token_client.balance(&operator), 3_000_001);
+    assert_eq!(token_client.balance(&buyer), 0);
+
+    // Every beneficiary sees the plan among their contributions
+    for address in [&lead, &co_author, &agent] {
+        let plans = client.get_contributor_plans(address);
+        assert_eq!(plans, Vec::from_array(&env, [plan_id.clone()]));
+    }
+}
+
+#[test]
+fn test_single_contributor_payout() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &10_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 101);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 101), "Solo", 80);
+    client.store_plan(&contributor, &input);
+    assert_eq!(
+        client.get_beneficiaries(&plan_id),
+        Vec::from_array(&env, [beneficiary(&contributor, 10_000)])
+    );
+
+    let record = client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(
+        record.payouts,
+        Vec::from_array(&env, [Payout { recipient: contributor.clone(), amount: 7_000_000 }])
+    );
+}
+
+#[test]
+fn test_invalid_beneficiaries() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let mut input = make_input(&env, make_plan_id(&env, 102), make_content_hash(&env, 102), "Bad", 80);
+
+    let cases = [
+        // Weights must sum to 100%
+        Vec::from_array(&env, [beneficiary(&a, 5000), beneficiary(&b, 4000)]),
+        // No zero weights
+        Vec::from_array(&env, [beneficiary(&a, 10_000), beneficiary(&b, 0)]),
+        // No duplicates
+        Vec::from_array(&env, [beneficiary(&a, 5000), beneficiary(&a, 5000)]),
+    ];
+    for beneficiaries in cases {
+        input.beneficiaries = beneficiaries;
+        assert_eq!(
+            client.try_store_plan(&a, &input),
+            Err(Ok(SynapseError::InvalidBeneficiaries))
+        );
+    }
+}
+
+#[test]
+fn test_accrue_and_claim() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &20_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_id = make_plan_id(&env, 110);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 110), "Accrued", 80);
+    client.store_plan(&contributor, &input);
+
+    assert_eq!(client.get_settlement_mode(), SettlementMode::Push);
+    client.set_settlement_mode(&SettlementMode::Accrue);
+
+    let second_buyer = Address::generate(&env);
+    token_admin_client.mint(&second_buyer, &10_000_000_i128);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    client.purchase_plan(&second_buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+
+    // The contract holds the funds until claimed
+    assert_eq!(token_client.balance(&client.address), 20_000_000);
+    assert_eq!(token_client.balance(&contributor), 0);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 14_000_000);
+    assert_eq!(client.get_claimable(&operator, &native_token), 6_000_000);
+
+    assert_eq!(client.claim(&contributor, &native_token), 14_000_000);
+    assert_eq!(env.auths()[0].0, contributor);
+    assert_eq!(token_client.balance(&contributor), 14_000_000);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 0);
+    assert_eq!(client.try_claim(&contributor, &native_token), Err(Ok(SynapseError::NothingToClaim)));
+
+    client.claim(&operator, &native_token);
+    assert_eq!(token_client.balance(&operator), 6_000_000);
+    assert_eq!(token_client.balance(&client.address), 0);
+}
+
+#[test]
+fn test_claim_after_switching_back_to_push() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    token_admin_client.mint(&buyer, &20_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_id = make_plan_id(&env, 111);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 111), "Mixed", 80);
+    client.store_plan(&contributor, &input);
+
+    let second_buyer = Address::generate(&env);
+    token_admin_client.mint(&second_buyer, &10_000_000_i128);
+
+    client.set_settlement_mode(&SettlementMode::Accrue);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    client.set_settlement_mode(&SettlementMode::Push);
+    client.purchase_plan(&second_buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+
+    assert_eq!(token_client.balance(&contributor), 7_000_000);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 7_000_000);
+    client.claim(&contributor, &native_token);
+    assert_eq!(token_client.balance(&contributor), 14_000_000);
+}
+
+#[test]
+fn test_purchase_in_second_token() {
+    let (env, client, admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let usdc = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &usdc).mint(&buyer, &5_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 120);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 120), "Stable", 80);
+    client.store_plan(&contributor, &input);
+
+    // Not yet on the allowlist
+    assert_eq!(
+        client.try_set_price(&contributor, &plan_id, &usdc, &2_000_000_i128),
+        Err(Ok(SynapseError::TokenNotAccepted))
+    );
+    let result = client.try_purchase_plan(&buyer, &plan_id, &usdc, &2_000_000_i128, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::TokenNotAccepted));
+
+    client.add_token(&usdc);
+    assert_eq!(
+        client.get_accepted_tokens(),
+        Vec::from_array(&env, [native_token.clone(), usdc.clone()])
+    );
+
+    // Accepted, but the contributor has not priced the plan in it
+    assert_eq!(
+        client.try_get_plan_price(&plan_id, &usdc),
+        Err(Ok(SynapseError::TokenNotPriced))
+    );
+    let result = client.try_purchase_plan(&buyer, &plan_id, &usdc, &2_000_000_i128, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::TokenNotPriced));
+
+    // Prices in the token need admin bounds, like native prices
+    assert_eq!(client.get_token_price_bounds(&usdc), None);
+    assert_eq!(
+        client.try_set_price(&contributor, &plan_id, &usdc, &2_000_000_i128),
+        Err(Ok(SynapseError::TokenBoundsNotSet))
+    );
+    client.set_token_price_bounds(&usdc, &1_000_000_i128, &5_000_000_i128);
+    assert_eq!(client.get_token_price_bounds(&usdc), Some((1_000_000, 5_000_000)));
+    assert_eq!(
+        client.try_set_price(&contributor, &plan_id, &usdc, &1_i128),
+        Err(Ok(SynapseError::PriceOutOfBounds))
+    );
+    assert_eq!(
+        client.try_set_price(&contributor, &plan_id, &usdc, &5_000_001_i128),
+        Err(Ok(SynapseError::PriceOutOfBounds))
+    );
+    assert_eq!(
+        client.get_token_price_bounds(&native_token),
+        Some(client.get_price_bounds())
+    );
+
+    client.set_price(&contributor, &plan_id, &usdc, &2_000_000_i128);
+    assert_eq!(client.get_plan_price(&plan_id, &usdc), 2_000_000);
+    // The native price is untouched
+    assert_eq!(client.get_plan_price(&plan_id, &native_token), 10_000_000);
+
+    let record = client.purchase_plan(&buyer, &plan_id, &usdc, &2_000_000_i128, &None);
+    assert_eq!(record.token, usdc);
+    assert_eq!(record.amount_stroops, 2_000_000);
+
+    let usdc_client = soroban_sdk::token::Client::new(&env, &usdc);
+    assert_eq!(usdc_client.balance(&contributor), 1_400_000);
+    assert_eq!(usdc_client.balance(&operator), 600_000);
+    assert_eq!(usdc_client.balance(&buyer), 3_000_000);
+
+    client.remove_token(&usdc);
+    let result = client.try_purchase_plan(&buyer, &plan_id, &usdc, &2_000_000_i128, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::TokenNotAccepted));
+    assert_eq!(client.try_remove_token(&usdc), Err(Ok(SynapseError::TokenNotAccepted)));
+}
+
+#[test]
+fn test_accrue_per_token() {
+    let (env, client, admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let usdc = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &usdc).mint(&buyer, &2_000_000_i128);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &10_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 121);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 121), "Both", 80);
+    client.store_plan(&contributor, &input);
+    client.add_token(&usdc);
+    client.set_token_price_bounds(&usdc, &1_000_000_i128, &5_000_000_i128);
+    client.set_price(&contributor, &plan_id, &usdc, &2_000_000_i128);
+    client.set_settlement_mode(&SettlementMode::Accrue);
+
+    let second_buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token)
+        .mint(&second_buyer, &10_000_000_i128);
+    client.purchase_plan(&buyer, &plan_id, &usdc, &2_000_000_i128, &None);
+    client.purchase_plan(&second_buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+
+    assert_eq!(client.get_claimable(&contributor, &usdc), 1_400_000);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 7_000_000);
+
+    assert_eq!(client.claim(&contributor, &usdc), 1_400_000);
+    assert_eq!(soroban_sdk::token::Client::new(&env, &usdc).balance(&contributor), 1_400_000);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 7_000_000);
+}
+
+#[test]
+fn test_deposit_and_withdraw() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &50_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    assert_eq!(client.deposit(&buyer, &native_token, &30_000_000_i128), 30_000_000);
+    assert_eq!(env.auths()[0].0, buyer);
+    assert_eq!(client.get_balance(&buyer, &native_token), 30_000_000);
+    assert_eq!(token_client.balance(&buyer), 20_000_000);
+    assert_eq!(token_client.balance(&client.address), 30_000_000);
+
+    assert_eq!(client.withdraw(&buyer, &native_token, &10_000_000_i128), 20_000_000);
+    assert_eq!(token_client.balance(&buyer), 30_000_000);
+    assert_eq!(
+        client.try_withdraw(&buyer, &native_token, &20_000_001_i128),
+        Err(Ok(SynapseError::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_deposit(&buyer, &native_token, &0_i128),
+        Err(Ok(SynapseError::InvalidAmount))
+    );
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_deposit(&buyer, &other, &1_i128),
+        Err(Ok(SynapseError::TokenNotAccepted))
+    );
+}
+
+#[test]
+fn test_purchase_debits_prepaid_balance() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &25_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_id = make_plan_id(&env, 130);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 130), "Prepaid", 80);
+    client.store_plan(&contributor, &input);
+
+    client.deposit(&buyer, &native_token, &15_000_000_i128);
+
+    // Covered by the balance: wallet untouched
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(client.get_balance(&buyer, &native_token), 5_000_000);
+    assert_eq!(token_client.balance(&buyer), 10_000_000);
+    assert_eq!(token_client.balance(&contributor), 7_000_000);
+    assert_eq!(token_client.balance(&operator), 3_000_000);
+    assert_eq!(token_client.balance(&client.address), 5_000_000);
+
+    // Balance too small: paid from the wallet instead
+    let next_id = make_plan_id(&env, 132);
+    let input = make_input(&env, next_id.clone(), make_content_hash(&env, 132), "Wallet", 80);
+    client.store_plan(&contributor, &input);
+    client.purchase_plan(&buyer, &next_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(client.get_balance(&buyer, &native_token), 5_000_000);
+    assert_eq!(token_client.balance(&buyer), 0);
+    assert_eq!(token_client.balance(&contributor), 14_000_000);
+}
+
+#[test]
+fn test_prepaid_purchase_in_accrue_mode() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &10_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_id = make_plan_id(&env, 131);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 131), "Custody", 80);
+    client.store_plan(&contributor, &input);
+    client.set_settlement_mode(&SettlementMode::Accrue);
+
+    client.deposit(&buyer, &native_token, &10_000_000_i128);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+
+    assert_eq!(client.get_balance(&buyer, &native_token), 0);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 7_000_000);
+    assert_eq!(client.get_claimable(&operator, &native_token), 3_000_000);
+    assert_eq!(token_client.balance(&client.address), 10_000_000);
+}
+
+#[test]
+fn test_voucher_channel_lifecycle() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &50_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_a = make_plan_id(&env, 140);
+    let plan_b = make_plan_id(&env, 141);
+    client.store_plan(&contributor, &make_input(&env, plan_a.clone(), make_content_hash(&env, 140), "A", 80));
+    client.store_plan(&contributor, &make_input(&env, plan_b.clone(), make_content_hash(&env, 141), "B", 80));
+
+    let key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+    let signer = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    let channel = client.open_channel(&buyer, &native_token, &signer, &30_000_000_i128);
+    assert_eq!(token_client.balance(&client.address), 30_000_000);
+    assert_eq!(
+        client.try_open_channel(&buyer, &native_token, &signer, &1_i128),
+        Err(Ok(SynapseError::ChannelExists))
+    );
+
+    let mut voucher = Voucher {
+        contract: client.address.clone(),
+        channel_id: channel.id,
+        buyer: buyer.clone(),
+        token: native_token.clone(),
+        nonce: 1,
+        plan_ids: Vec::from_array(&env, [plan_a.clone()]),
+        total: 10_000_000,
+    };
+    let first = sign_voucher(&env, &key, &voucher);
+
+    // The buyer keeps recalling; the latest voucher supersedes the first
+    voucher.nonce = 2;
+    voucher.plan_ids.push_back(plan_b.clone());
+    voucher.total = 20_000_000;
+    let latest = sign_voucher(&env, &key, &voucher);
+
+    let records = client.redeem_voucher(&contributor, &voucher, &latest);
+    assert_eq!(records.len(), 2);
+    assert_eq!(token_client.balance(&contributor), 14_000_000);
+    assert_eq!(token_client.balance(&operator), 6_000_000);
+    assert_eq!(client.get_plan(&plan_a).unwrap().purchase_count, 1);
+    assert_eq!(client.get_channel(&buyer, &native_token).unwrap().redeemed, 20_000_000);
+
+    // Replaying either voucher is rejected
+    assert_eq!(
+        client.try_redeem_voucher(&contributor, &voucher, &latest).unwrap_err(),
+        Ok(SynapseError::InvalidVoucher)
+    );
+    let mut stale = voucher.clone();
+    stale.nonce = 1;
+    stale.plan_ids = Vec::from_array(&env, [plan_a.clone()]);
+    stale.total = 10_000_000;
+    assert_eq!(
+        client.try_redeem_voucher(&contributor, &stale, &first).unwrap_err(),
+        Ok(SynapseError::InvalidVoucher)
+    );
+
+    // Buyer closes; after the dispute window the rest is refunded
+    let closes_at = client.close_channel(&buyer, &native_token);
+    assert_eq!(
+        client.try_finalize_channel(&buyer, &native_token),
+        Err(Ok(SynapseError::DisputeWindowActive))
+    );
+    env.ledger().with_mut(|li| li.sequence_number = closes_at + 1);
+    voucher.nonce = 3;
+    voucher.plan_ids.push_back(plan_a.clone());
+    voucher.total = 30_000_000;
+    let late = sign_voucher(&env, &key, &voucher);
+    assert_eq!(
+        client.try_redeem_voucher(&operator, &voucher, &late).unwrap_err(),
+        Ok(SynapseError::ChannelClosed)
+    );
+    assert_eq!(client.finalize_channel(&buyer, &native_token), 10_000_000);
+    assert_eq!(token_client.balance(&buyer), 30_000_000);
+    assert_eq!(client.get_channel(&buyer, &native_token), None);
+}
+
+#[test]
+fn test_voucher_settles_owned_plan() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &50_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_a = make_plan_id(&env, 143);
+    let plan_b = make_plan_id(&env, 144);
+    let input = make_input(&env, plan_a.clone(), make_content_hash(&env, 143), "A", 80);
+    client.store_plan(&contributor, &input);
+    let input = make_input(&env, plan_b.clone(), make_content_hash(&env, 144), "B", 80);
+    client.store_plan(&contributor, &input);
+
+    // Bought outright before the channel was opened
+    client.purchase_plan(&buyer, &plan_a, &native_token, &10_000_000_i128, &None);
+    assert_eq!(token_client.balance(&contributor), 7_000_000);
+
+    let key = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+    let signer = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    let channel = client.open_channel(&buyer, &native_token, &signer, &30_000_000_i128);
+    let mut voucher = Voucher {
+        contract: client.address.clone(),
+        channel_id: channel.id,
+        buyer: buyer.clone(),
+        token: native_token.clone(),
+        nonce: 1,
+        plan_ids: Vec::from_array(&env, [plan_a.clone(), plan_b.clone(), plan_b.clone()]),
+        total: 10_000_000,
+    };
+
+    // Owned and repeated plans are passed over without a second charge
+    let sig = sign_voucher(&env, &key, &voucher);
+    assert_eq!(client.redeem_voucher(&contributor, &voucher, &sig).len(), 1);
+    assert_eq!(token_client.balance(&contributor), 14_000_000);
+    assert_eq!(client.get_purchases(&plan_a).len(), 1);
+    assert_eq!(client.get_purchases(&plan_b).len(), 1);
+    let channel = client.get_channel(&buyer, &native_token).unwrap();
+    assert_eq!((channel.redeemed, channel.settled), (10_000_000, 3));
+
+    // A later voucher listing only owned plans settles for nothing
+    voucher.nonce = 2;
+    voucher.plan_ids.push_back(plan_a.clone());
+    let sig = sign_voucher(&env, &key, &voucher);
+    assert_eq!(client.redeem_voucher(&contributor, &voucher, &sig).len(), 0);
+    assert_eq!(token_client.balance(&contributor), 14_000_000);
+    let channel = client.get_channel(&buyer, &native_token).unwrap();
+    assert_eq!((channel.redeemed, channel.settled), (10_000_000, 4));
+}
+
+#[test]
+fn test_voucher_settles_plan_delisted_after_signing() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &50_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_a = make_plan_id(&env, 145);
+    let plan_b = make_plan_id(&env, 146);
+    let input = make_input(&env, plan_a.clone(), make_content_hash(&env, 145), "A", 80);
+    client.store_plan(&alice, &input);
+    let input = make_input(&env, plan_b.clone(), make_content_hash(&env, 146), "B", 80);
+    client.store_plan(&bob, &input);
+
+    let key = ed25519_dalek::SigningKey::from_bytes(&[10u8; 32]);
+    let signer = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    let channel = client.open_channel(&buyer, &native_token, &signer, &30_000_000_i128);
+    let voucher = Voucher {
+        contract: client.address.clone(),
+        channel_id: channel.id,
+        buyer: buyer.clone(),
+        token: native_token.clone(),
+        nonce: 1,
+        plan_ids: Vec::from_array(&env, [plan_a.clone(), plan_b.clone()]),
+        total: 20_000_000,
+    };
+    let sig = sign_voucher(&env, &key, &voucher);
+
+    // Plan A is delisted before the voucher is redeemed
+    client.remove_plan(&plan_a, &2u32);
+    assert_eq!(client.redeem_voucher(&bob, &voucher, &sig).len(), 2);
+    assert_eq!(token_client.balance(&alice), 7_000_000);
+    assert_eq!(token_client.balance(&bob), 7_000_000);
+
+    // Direct purchases of the delisted plan are still refused
+    let result = client.try_purchase_plan(&buyer, &plan_a, &native_token, &10_000_000_i128, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::PlanNotActive));
+}
+
+#[test]
+fn test_voucher_rejects_bad_signature_and_overspend() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &50_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 142);
+    client.store_plan(&contributor, &make_input(&env, plan_id.clone(), make_content_hash(&env, 142), "C", 80));
+
+    let key = ed25519_dalek::SigningKey::from_bytes(&[8u8; 32]);
+    let signer = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    let channel = client.open_channel(&buyer, &native_token, &signer, &15_000_000_i128);
+
+    let mut voucher = Voucher {
+        contract: client.address.clone(),
+        channel_id: channel.id,
+        buyer: buyer.clone(),
+        token: native_token.clone(),
+        nonce: 1,
+        plan_ids: Vec::from_array(&env, [plan_id.clone()]),
+        total: 5_000_000,
+    };
+
+    // Total below the plan price
+    let sig = sign_voucher(&env, &key, &voucher);
+    assert_eq!(
+        client.try_redeem_voucher(&contributor, &voucher, &sig).unwrap_err(),
+        Ok(SynapseError::Underpayment)
+    );
+
+    // Total beyond the deposit
+    voucher.total = 20_000_000;
+    let sig = sign_voucher(&env, &key, &voucher);
+    assert_eq!(
+        client.try_redeem_voucher(&contributor, &voucher, &sig).unwrap_err(),
+        Ok(SynapseError::InsufficientBalance)
+    );
+
+    // Only the operator or a contributor may redeem
+    voucher.total = 10_000_000;
+    let sig = sign_voucher(&env, &key, &voucher);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_redeem_voucher(&stranger, &voucher, &sig).unwrap_err(),
+        Ok(SynapseError::Unauthorized)
+    );
+
+    // Signed by someone else
+    let forger = ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]);
+    let forged = sign_voucher(&env, &forger, &voucher);
+    assert!(client.try_redeem_voucher(&contributor, &voucher, &forged).is_err());
+
+    client.redeem_voucher(&contributor, &voucher, &sig);
+    assert_eq!(client.get_channel(&buyer, &native_token).unwrap().redeemed, 10_000_000);
+}
+
+#[test]
+fn test_has_access_and_no_double_charge() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &20_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_id = make_plan_id(&env, 150);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 150), "Owned", 80);
+    client.store_plan(&contributor, &input);
+
+    assert!(!client.has_access(&buyer, &plan_id));
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    assert!(client.has_access(&buyer, &plan_id));
+    assert!(!client.has_access(&contributor, &plan_id));
+
+    assert_eq!(
+        client
+            .try_purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None)
+            .unwrap_err(),
+        Ok(SynapseError::AlreadyPurchased)
+    );
+    assert_eq!(token_client.balance(&buyer), 10_000_000);
+    assert_eq!(client.get_purchases(&plan_id).len(), 1);
+}
+
+#[test]
+fn test_time_limited_license() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &30_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 160);
+    let mut input = make_input(&env, plan_id.clone(), make_content_hash(&env, 160), "Rental", 80);
+    input.license = LicenseModel::Duration(100);
+    client.store_plan(&contributor, &input);
+    assert_eq!(client.get_license(&plan_id), LicenseModel::Duration(100));
+
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(
+        client.get_entitlement(&buyer, &plan_id),
+        Some(Entitlement { granted_at_ledger: 100, expires_at_ledger: Some(200) })
+    );
+
+    // Renewing early stacks onto the remaining time
+    env.ledger().with_mut(|li| li.sequence_number = 150);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    let entitlement = client.get_entitlement(&buyer, &plan_id).unwrap();
+    assert_eq!(entitlement.expires_at_ledger, Some(300));
+
+    env.ledger().with_mut(|li| li.sequence_number = 299);
+    assert!(client.has_access(&buyer, &plan_id));
+    env.ledger().with_mut(|li| li.sequence_number = 300);
+    assert!(!client.has_access(&buyer, &plan_id));
+
+    // A lapsed license renews from now
+    env.ledger().with_mut(|li| li.sequence_number = 500);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+    let entitlement = client.get_entitlement(&buyer, &plan_id).unwrap();
+    assert_eq!(entitlement.expires_at_ledger, Some(600));
+    assert!(client.has_access(&buyer, &plan_id));
+}
+
+#[test]
+fn test_invalid_license_duration() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 161);
+    let mut input = make_input(&env, plan_id.clone(), make_content_hash(&env, 161), "Zero", 80);
+    input.license = LicenseModel::Duration(0);
+    assert_eq!(
+        client.try_store_plan(&contributor, &input),
+        Err(Ok(SynapseError::InvalidLicense))
+    );
+}
+
+#[test]
+fn test_subscription_pass_and_distribution() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let subscriber = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token)
+        .mint(&subscriber, &100_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_a = make_plan_id(&env, 170);
+    let plan_b = make_plan_id(&env, 171);
+    let input = make_input(&env, plan_a.clone(), make_content_hash(&env, 170), "A", 80);
+    client.store_plan(&alice, &input);
+    let input = make_input(&env, plan_b.clone(), make_content_hash(&env, 171), "B", 80);
+    client.store_plan(&bob, &input);
+
+    assert_eq!(
+        client.try_buy_subscription(&subscriber),
+        Err(Ok(SynapseError::SubscriptionNotConfigured))
+    );
+    let product = SubscriptionProduct {
+        token: native_token.clone(),
+        price: 40_000_000,
+        duration: 1_000,
+    };
+    client.set_subscription(&Some(product.clone()));
+    assert_eq!(client.get_subscription(), Some(product));
+
+    env.ledger().with_mut(|li| li.sequence_number = 1_200);
+    let pass = client.buy_subscription(&subscriber);
+    assert_eq!(pass.expires_at_ledger, 2_200);
+    assert_eq!(token_client.balance(&subscriber), 60_000_000);
+    assert_eq!(client.get_claimable(&operator, &native_token), 12_000_000);
+    assert_eq!(client.get_subscription_pool(&1, &native_token), 28_000_000);
+    assert!(client.has_access(&subscriber, &plan_a));
+
+    // The pass covers only listed plans
+    assert!(!client.has_access(&subscriber, &make_plan_id(&env, 172)));
+    let plan_c = make_plan_id(&env, 173);
+    let input = make_input(&env, plan_c.clone(), make_content_hash(&env, 173), "C", 80);
+    client.store_plan(&alice, &input);
+    client.remove_plan(&plan_c, &1u32);
+    assert!(!client.has_access(&subscriber, &plan_c));
+
+    // Recalls under the pass are free and counted once per plan and period
+    client.purchase_plan(&subscriber, &plan_a, &native_token, &0_i128, &None);
+    client.purchase_plan(&subscriber, &plan_a, &native_token, &0_i128, &None);
+    client.purchase_plan(&subscriber, &plan_b, &native_token, &0_i128, &None);
+    assert_eq!(token_client.balance(&subscriber), 60_000_000);
+    assert_eq!(client.get_recalls(&1, &None), 2);
+    assert_eq!(client.get_recalls(&1, &Some(plan_a.clone())), 1);
+
+    let ids = Vec::from_array(&env, [plan_a.clone(), plan_b.clone()]);
+    assert_eq!(
+        client.try_distribute_subscription(&1, &native_token, &ids),
+        Err(Ok(SynapseError::PeriodNotEnded))
+    );
+
+    env.ledger().with_mut(|li| li.sequence_number = 2_000);
+    assert_eq!(client.distribute_subscription(&1, &native_token, &ids), 28_000_000);
+    assert_eq!(client.get_claimable(&alice, &native_token), 14_000_000);
+    assert_eq!(client.get_claimable(&bob, &native_token), 14_000_000);
+    // Each plan is paid once per period
+    assert_eq!(client.distribute_subscription(&1, &native_token, &ids), 0);
+
+    // The pass lapses; purchases are charged again
+    env.ledger().with_mut(|li| li.sequence_number = 2_200);
+    assert!(!client.has_access(&subscriber, &plan_b));
+    client.purchase_plan(&subscriber, &plan_b, &native_token, &10_000_000_i128, &None);
+    assert_eq!(token_client.balance(&subscriber), 50_000_000);
+}
+
+#[test]
+fn test_subscription_remainder_goes_to_operator() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let subscriber = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token)
+        .mint(&subscriber, &10_000_000_i128);
+
+    let mut plan_ids = Vec::new(&env);
+    for seed in 174..177u8 {
+        let plan_id = make_plan_id(&env, seed);
+        let input = make_input(&env, plan_id.clone(), make_content_hash(&env, seed), "P", 80);
+        client.store_plan(&contributor, &input);
+        plan_ids.push_back(plan_id);
+    }
+    client.set_subscription(&Some(SubscriptionProduct {
+        token: native_token.clone(),
+        price: 10_000_000,
+        duration: 500,
+    }));
+    client.buy_subscription(&subscriber);
+    for plan_id in plan_ids.iter() {
+        client.purchase_plan(&subscriber, &plan_id, &native_token, &0_i128, &None);
+    }
+
+    // A third of the 7 XLM pool each; the leftover stroop waits for the last plan
+    env.ledger().with_mut(|li| li.sequence_number = 500);
+    let first = Vec::from_array(&env, [plan_ids.get(0).unwrap(), plan_ids.get(1).unwrap()]);
+    assert_eq!(client.distribute_subscription(&0, &native_token, &first), 4_666_666);
+    assert_eq!(client.get_claimable(&operator, &native_token), 3_000_000);
+    assert_eq!(client.distribute_subscription(&0, &native_token, &plan_ids), 2_333_334);
+    assert_eq!(client.get_claimable(&contributor, &native_token), 6_999_999);
+    assert_eq!(client.get_claimable(&operator, &native_token), 3_000_001);
+    assert_eq!(client.distribute_subscription(&0, &native_token, &plan_ids), 0);
+}
+
+#[test]
+fn test_subscription_unrecalled_pool_and_fixed_period() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let subscriber = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token)
+        .mint(&subscriber, &10_000_000_i128);
+
+    let product = SubscriptionProduct {
+        token: native_token.clone(),
+        price: 10_000_000,
+        duration: 500,
+    };
+    client.set_subscription(&Some(product.clone()));
+    client.buy_subscription(&subscriber);
+
+    let mut longer = product.clone();
+    longer.duration = 600;
+    assert_eq!(
+        client.try_set_subscription(&Some(longer)),
+        Err(Ok(SynapseError::InvalidSubscription))
+    );
+    longer = product.clone();
+    longer.price = 0;
+    assert_eq!(
+        client.try_set_subscription(&Some(longer)),
+        Err(Ok(SynapseError::InvalidSubscription))
+    );
+
+    // Nothing was recalled, so the operator takes the pool
+    env.ledger().with_mut(|li| li.sequence_number = 500);
+    assert_eq!(client.distribute_subscription(&0, &native_token, &Vec::new(&env)), 7_000_000);
+    assert_eq!(client.get_claimable(&operator, &native_token), 10_000_000);
+    assert_eq!(client.get_subscription_pool(&0, &native_token), 0);
+
+    client.set_subscription(&None);
+    assert_eq!(client.get_subscription(), None);
+}
+
+#[test]
+fn test_purchase_plans_batch() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &50_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let mut ids = Vec::new(&env);
+    for (i, contributor) in [(180u8, &alice), (181, &alice), (182, &bob)] {
+        let plan_id = make_plan_id(&env, i);
+        let input = make_input(&env, plan_id.clone(), make_content_hash(&env, i), "Batch", 80);
+        client.store_plan(contributor, &input);
+        ids.push_back(plan_id);
+    }
+
+    assert_eq!(
+        client.try_purchase_plans(&buyer, &ids, &native_token, &29_999_999_i128).unwrap_err(),
+        Ok(SynapseError::Underpayment)
+    );
+
+    let records = client.purchase_plans(&buyer, &ids, &native_token, &30_000_000_i128);
+    assert_eq!(records.len(), 3);
+    assert_eq!(token_client.balance(&buyer), 20_000_000);
+    assert_eq!(token_client.balance(&alice), 14_000_000);
+    assert_eq!(token_client.balance(&bob), 7_000_000);
+    assert_eq!(token_client.balance(&operator), 9_000_000);
+    for plan_id in ids.iter() {
+        assert!(client.has_access(&buyer, &plan_id));
+        assert_eq!(client.get_purchases(&plan_id).len(), 1);
+    }
+    assert_eq!(client.get_stats().total_purchases, 3);
+
+    // All-or-nothing: one owned plan fails the whole batch
+    let fresh = make_plan_id(&env, 183);
+    let input = make_input(&env, fresh.clone(), make_content_hash(&env, 183), "Fresh", 80);
+    client.store_plan(&bob, &input);
+    let mixed = Vec::from_array(&env, [fresh.clone(), ids.get(0).unwrap()]);
+    assert_eq!(
+        client.try_purchase_plans(&buyer, &mixed, &native_token, &20_000_000_i128).unwrap_err(),
+        Ok(SynapseError::AlreadyPurchased)
+    );
+    assert!(!client.has_access(&buyer, &fresh));
+    assert_eq!(token_client.balance(&buyer), 20_000_000);
+
+    let dup = Vec::from_array(&env, [fresh.clone(), fresh.clone()]);
+    assert_eq!(
+        client.try_purchase_plans(&buyer, &dup, &native_token, &20_000_000_i128).unwrap_err(),
+        Ok(SynapseError::DuplicatePlan)
+    );
+    let empty = Vec::new(&env);
+    assert_eq!(
+        client.try_purchase_plans(&buyer, &empty, &native_token, &0_i128).unwrap_err(),
+        Ok(SynapseError::InvalidAmount)
+    );
+}
+
+#[test]
+fn test_pay_search() {
+    let (env, client, admin, operator, native_token) = setup_env();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &10_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let plan_a = make_plan_id(&env, 190);
+    let plan_b = make_plan_id(&env, 191);
+    let input = make_input(&env, plan_a.clone(), make_content_hash(&env, 190), "A", 80);
+    client.store_plan(&alice, &input);
+    let input = make_input(&env, plan_b.clone(), make_content_hash(&env, 191), "B", 80);
+    client.store_plan(&bob, &input);
+    let query = BytesN::from_array(&env, &[3u8; 32]);
+    let results = Vec::from_array(&env, [plan_a.clone(), plan_b.clone()]);
+
+    // By default the operator keeps the whole 0.2 XLM
+    assert_eq!(client.get_search_fee(), (2_000_000, 0));
+    let receipt = client.pay_search(&buyer, &query, &results);
+    assert_eq!((receipt.id, receipt.fee, receipt.contributor_share), (1, 2_000_000, 0));
+    assert_eq!(token_client.balance(&operator), 2_000_000);
+    assert_eq!(client.get_search_receipt(&1), Some(receipt));
+
+    // Share half of a fee that doesn't divide evenly
+    client.set_search_fee(&2_000_001_i128, &5_000u32);
+    let receipt = client.pay_search(&buyer, &query, &results);
+    assert_eq!(receipt.id, 2);
+    assert_eq!(receipt.contributor_share, 1_000_000);
+    assert_eq!(token_client.balance(&alice), 500_000);
+    assert_eq!(token_client.balance(&bob), 500_000);
+    assert_eq!(token_client.balance(&operator), 3_000_001);
+    assert_eq!(token_client.balance(&buyer), 5_999_999);
+
+    assert_eq!(
+        client.try_set_search_fee(&1_i128, &10_001u32),
+        Err(Ok(SynapseError::InvalidShareBps))
+    );
+    let mut many = Vec::new(&env);
+    for _ in 0..21 {
+        many.push_back(plan_a.clone());
+    }
+    assert_eq!(
+        client.try_pay_search(&buyer, &query, &many),
+        Err(Ok(SynapseError::TooManyResults))
+    );
+    let repeated = Vec::from_array(&env, [plan_a.clone(), plan_b.clone(), plan_a.clone()]);
+    assert_eq!(
+        client.try_pay_search(&buyer, &query, &repeated),
+        Err(Ok(SynapseError::DuplicatePlan))
+    );
+
+    client.pause(&admin, &PausableOp::PaySearch);
+    assert_eq!(
+        client.try_pay_search(&buyer, &query, &results),
+        Err(Ok(SynapseError::Paused))
+    );
+}
+
+#[test]
+fn test_purchase_with_referrer() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let referrer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &30_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let mut ids = Vec::new(&env);
+    for i in 200u8..203 {
+        let plan_id = make_plan_id(&env, i);
+        let input = make_input(&env, plan_id.clone(), make_content_hash(&env, i), "Referred", 80);
+        client.store_plan(&contributor, &input);
+        ids.push_back(plan_id);
+    }
+    let referred = Some(referrer.clone());
+
+    // No referral share configured: the referrer is recorded but unpaid
+    let price = 10_000_000_i128;
+    let first = ids.get(0).unwrap();
+    let record = client.purchase_plan(&buyer, &first, &native_token, &price, &referred);
+    assert_eq!(record.referrer, referred);
+    assert_eq!((record.operator_share, record.referral_share), (3_000_000, 0));
+
+    assert_eq!(
+        client.try_set_referral_share_bps(&10_001u32),
+        Err(Ok(SynapseError::InvalidShareBps))
+    );
+    client.set_referral_share_bps(&2_500u32);
+    assert_eq!(client.get_referral_share_bps(), 2_500);
+
+    let second = ids.get(1).unwrap();
+    let record = client.purchase_plan(&buyer, &second, &native_token, &price, &referred);
+    assert_eq!(record.contributor_share, 7_000_000);
+    assert_eq!((record.operator_share, record.referral_share), (2_250_000, 750_000));
+    assert_eq!(token_client.balance(&referrer), 750_000);
+    assert_eq!(token_client.balance(&operator), 5_250_000);
+
+    // Without a referrer the operator keeps its full share
+    let record = client.purchase_plan(&buyer, &ids.get(2).unwrap(), &native_token, &price, &None);
+    assert_eq!((record.operator_share, record.referral_share), (3_000_000, 0));
+
+    let self_referred = Some(buyer.clone());
+    assert_eq!(
+        client
+            .try_purchase_plan(&buyer, &first, &native_token, &price, &self_referred)
+            .unwrap_err(),
+        Ok(SynapseError::SelfReferral)
+    );
+}
+
+#[test]
+fn test_derivative_royalties() {
+    let (env, client, _admin, operator, native_token) = setup_env();
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &20_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    // A chain of five plans, each by a different author, each extending the last
+    let mut authors = std::vec::Vec::new();
+    let mut parent: Option<BytesN<16>> = None;
+    for i in 210u8..215 {
+        let author = Address::generate(&env);
+        let plan_id = make_plan_id(&env, i);
+        let mut input = make_input(&env, plan_id.clone(), make_content_hash(&env, i), "Gen", 80);
+        input.parent = parent.clone();
+        client.store_plan(&author, &input);
+        authors.push(author);
+        parent = Some(plan_id);
+    }
+    let root = make_plan_id(&env, 210);
+    let leaf = make_plan_id(&env, 214);
+    assert_eq!(client.get_parent(&leaf), Some(make_plan_id(&env, 213)));
+    assert_eq!(client.get_derivatives(&root), Vec::from_array(&env, [make_plan_id(&env, 211)]));
+
+    // No royalty configured: the derivative's author keeps everything
+    let middle = make_plan_id(&env, 212);
+    client.purchase_plan(&buyer, &middle, &native_token, &10_000_000_i128, &None);
+    assert_eq!(token_client.balance(&authors[2]), 7_000_000);
+
+    // 10% per generation, capped at three ancestors
+    client.set_royalty_bps(&1_000u32);
+    let record = client.purchase_plan(&buyer, &leaf, &native_token, &10_000_000_i128, &None);
+    assert_eq!(record.contributor_share, 7_000_000);
+    assert_eq!(token_client.balance(&authors[4]), 6_300_000);
+    assert_eq!(token_client.balance(&authors[3]), 630_000);
+    assert_eq!(token_client.balance(&authors[2]), 7_000_000 + 63_000);
+    assert_eq!(token_client.balance(&authors[1]), 7_000);
+    assert_eq!(token_client.balance(&authors[0]), 0);
+    assert_eq!(token_client.balance(&operator), 6_000_000);
+    assert_eq!(record.payouts.len(), 4);
+
+    let orphan = make_plan_id(&env, 215);
+    let mut input = make_input(&env, orphan.clone(), make_content_hash(&env, 215), "Orphan", 80);
+    input.parent = Some(make_plan_id(&env, 99));
+    assert_eq!(
+        client.try_store_plan(&authors[0], &input),
+        Err(Ok(SynapseError::PlanNotFound))
+    );
+}
+
+#[test]
+fn test_update_plan_content() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let other = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &10_000_000_i128);
+
+    let plan_id = make_plan_id(&env, 220);
+    let original = make_content_hash(&env, 220);
+    let input = make_input(&env, plan_id.clone(), original.clone(), "Versioned", 80);
+    client.store_plan(&contributor, &input);
+    client.purchase_plan(&buyer, &plan_id, &native_token, &10_000_000_i128, &None);
+
+    let v1 = client.get_plan_version(&plan_id, &1).unwrap();
+    assert_eq!((v1.version, v1.content_hash.clone()), (1, original.clone()));
+    assert_eq!(client.get_plan_version(&plan_id, &0), None);
+    assert_eq!(client.get_plan_version(&plan_id, &2), None);
+
+    let fixed = make_content_hash(&env, 221);
+    let cid = String::from_str(&env, "QmFixed");
+    env.ledger().with_mut(|li| li.timestamp = 1_700_000_000);
+    let v2 = client.update_plan_content(&contributor, &plan_id, &fixed, &cid);
+    assert_eq!(v2.version, 2);
+    assert_eq!(v2.created_at, 1_700_000_000);
+    assert_eq!(client.get_version_count(&plan_id), 2);
+    assert_eq!(client.get_plan_version(&plan_id, &1), Some(v1));
+    assert_eq!(client.get_plan_version(&plan_id, &2), Some(v2));
+
+    let plan = client.get_plan(&plan_id).unwrap();
+    assert_eq!((plan.content_hash, plan.ipfs_cid), (fixed.clone(), cid.clone()));
+    assert_eq!(plan.purchase_count, 1);
+    assert_eq!(client.get_purchases(&plan_id).len(), 1);
+
+    // Every version's hash stays reserved
+    assert!(client.content_exists(&original));
+    assert_eq!(
+        client.try_update_plan_content(&contributor, &plan_id, &original, &cid),
+        Err(Ok(SynapseError::ContentExists))
+    );
+    let copy = make_input(&env, make_plan_id(&env, 222), fixed.clone(), "Copy", 80);
+    assert_eq!(client.try_store_plan(&other, &copy), Err(Ok(SynapseError::ContentExists)));
+
+    assert_eq!(
+        client.try_update_plan_content(&other, &plan_id, &make_content_hash(&env, 223), &cid),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_update_plan_meta() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    let plan_id = make_plan_id(&env, 230);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 230), "Tpyo", 80);
+    client.store_plan(&contributor, &input);
+
+    let mut update = PlanMetaUpdate {
+        title: Some(String::from_str(&env, "Typo")),
+        description: None,
+        tags: Some(Vec::from_array(&env, [String::from_str(&env, "rust")])),
+        domain: None,
+        language: None,
+        framework: None,
+    };
+    client.update_plan_meta(&contributor, &plan_id, &update);
+    assert_eq!(env.auths()[0].0, contributor);
+
+    let plan = client.get_plan(&plan_id).unwrap();
+    assert_eq!(plan.title, String::from_str(&env, "Typo"));
+    assert_eq!(plan.tags.len(), 1);
+    assert_eq!(plan.description, String::from_str(&env, "Description"));
+    assert_eq!(plan.content_hash, make_content_hash(&env, 230));
+
+    // The admin may also edit; others may not
+    update.title = None;
+    update.tags = None;
+    update.framework = Some(String::from_str(&env, "soroban-sdk"));
+    client.update_plan_meta(&admin, &plan_id, &update);
+    assert_eq!(client.get_plan(&plan_id).unwrap().framework, String::from_str(&env, "soroban-sdk"));
+    assert_eq!(
+        client.try_update_plan_meta(&other, &plan_id, &update),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+
+    update.framework = None;
+    assert_eq!(
+        client.try_update_plan_meta(&contributor, &plan_id, &update),
+        Err(Ok(SynapseError::InvalidMetaUpdate))
+    );
+    update.title = Some(String::from_str(&env, ""));
+    assert_eq!(
+        client.try_update_plan_meta(&contributor, &plan_id, &update),
+        Err(Ok(SynapseError::InvalidMetaUpdate))
+    );
+}
+
+#[test]
+fn test_plan_status_lifecycle() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token).mint(&buyer, &20_000_000_i128);
+
+    let old_id = make_plan_id(&env, 240);
+    let new_id = make_plan_id(&env, 241);
+    let input = make_input(&env, old_id.clone(), make_content_hash(&env, 240), "Old", 80);
+    client.store_plan(&contributor, &input);
+    assert_eq!(client.get_plan_status(&old_id), PlanStatus::Active);
+
+    // With review on, new plans wait for approval
+    client.set_plan_review(&true);
+    let input = make_input(&env, new_id.clone(), make_content_hash(&env, 241), "New", 80);
+    client.store_plan(&contributor, &input);
+    assert_eq!(client.get_plan_status(&new_id), PlanStatus::Pending);
+    let price = 10_000_000_i128;
+    assert_eq!(
+        client.try_purchase_plan(&buyer, &new_id, &native_token, &price, &None).unwrap_err(),
+        Ok(SynapseError::PlanNotActive)
+    );
+    assert_eq!(
+        client.try_deprecate_plan(&contributor, &old_id, &Some(new_id.clone())),
+        Err(Ok(SynapseError::PlanNotActive))
+    );
+    client.activate_plan(&new_id);
+
+    client.deprecate_plan(&contributor, &old_id, &Some(new_id.clone()));
+    assert_eq!(client.get_plan_status(&old_id), PlanStatus::Deprecated(Some(new_id.clone())));
+    assert_eq!(
+        client.try_purchase_plan(&buyer, &old_id, &native_token, &price, &None).unwrap_err(),
+        Ok(SynapseError::PlanNotActive)
+    );
+    client.purchase_plan(&buyer, &new_id, &native_token, &10_000_000_i128, &None);
+
+    // Admin removal and reinstatement
+    client.remove_plan(&new_id, &7u32);
+    assert_eq!(client.get_plan_status(&new_id), PlanStatus::Removed(7));
+    assert_eq!(
+        client.try_remove_plan(&new_id, &8u32),
+        Err(Ok(SynapseError::InvalidStatusTransition))
+    );
+    assert_eq!(
+        client.try_deprecate_plan(&contributor, &new_id, &None),
+        Err(Ok(SynapseError::InvalidStatusTransition))
+    );
+    client.activate_plan(&new_id);
+    assert_eq!(client.get_plan_status(&new_id), PlanStatus::Active);
+    assert_eq!(client.try_activate_plan(&new_id), Err(Ok(SynapseError::InvalidStatusTransition)));
+
+    let other = Address::generate(&env);
+    assert_eq!(
+        client.try_deprecate_plan(&other, &new_id, &None),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_takedown_and_appeal() {
+    let (env, client, _admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let moderator = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let plan_id = make_plan_id(&env, 250);
+    let hash = make_content_hash(&env, 250);
+    let input = make_input(&env, plan_id.clone(), hash.clone(), "Bad", 80);
+    client.store_plan(&contributor, &input);
+    let revised = make_content_hash(&env, 251);
+    client.update_plan_content(&contributor, &plan_id, &revised, &String::from_str(&env, "QmRev"));
+    client.deprecate_plan(&contributor, &plan_id, &None);
+
+    assert_eq!(
+        client.try_takedown(&moderator, &plan_id, &3u32),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+    client.set_moderator(&Some(moderator.clone()));
+    client.takedown(&moderator, &plan_id, &3u32);
+    assert_eq!(env.auths()[0].0, moderator);
+
+    assert_eq!(client.get_plan_status(&plan_id), PlanStatus::Removed(3));
+    let takedown = client.get_takedown(&plan_id).unwrap();
+    assert_eq!((takedown.moderator, takedown.reason), (moderator.clone(), 3));
+    assert_eq!(takedown.prior_status, PlanStatus::Deprecated(None));
+    assert!(client.is_content_blocked(&hash));
+    assert!(client.is_content_blocked(&revised));
+    assert_eq!(
+        client.try_takedown(&moderator, &plan_id, &3u32),
+        Err(Ok(SynapseError::InvalidStatusTransition))
+    );
+    assert_eq!(client.try_activate_plan(&plan_id), Err(Ok(SynapseError::InvalidStatusTransition)));
+
+    // The content cannot come back under another plan
+    let reupload = make_input(&env, make_plan_id(&env, 252), hash.clone(), "Again", 80);
+    assert_eq!(client.try_store_plan(&stranger, &reupload), Err(Ok(SynapseError::ContentBlocked)));
+
+    // One appeal, decided by the admin
+    let statement = BytesN::from_array(&env, &[5u8; 32]);
+    assert_eq!(client.try_resolve_appeal(&plan_id, &true), Err(Ok(SynapseError::NoPendingAppeal)));
+    assert_eq!(
+        client.try_appeal(&stranger, &plan_id, &statement),
+        Err(Ok(SynapseError::Unauthorized))
+    );
+    client.appeal(&contributor, &plan_id, &statement);
+    let pending = AppealStatus::Pending(statement.clone());
+    assert_eq!(client.get_takedown(&plan_id).unwrap().appeal, pending);
+    assert_eq!(
+        client.try_appeal(&contributor, &plan_id, &statement),
+        Err(Ok(SynapseError::AppealNotAllowed))
+    );
+
+    // Upholding restores the status the plan had before the takedown
+    client.resolve_appeal(&plan_id, &true);
+    assert_eq!(client.get_plan_status(&plan_id), PlanStatus::Deprecated(None));
+    assert_eq!(client.get_takedown(&plan_id), None);
+    assert!(!client.is_content_blocked(&hash));
+}
+
+#[test]
+fn test_rejected_appeal_is_final() {
+    let (env, client, admin, _operator, _native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 253);
+    let hash = make_content_hash(&env, 253);
+    let input = make_input(&env, plan_id.clone(), hash.clone(), "Spam", 80);
+    client.store_plan(&contributor, &input);
+
+    client.takedown(&admin, &plan_id, &1u32);
+    let statement = BytesN::from_array(&env, &[6u8; 32]);
+    client.appeal(&contributor, &plan_id, &statement);
+    client.resolve_appeal(&plan_id, &false);
+
+    assert_eq!(client.get_takedown(&plan_id).unwrap().appeal, AppealStatus::Rejected);
+    assert!(client.is_content_blocked(&hash));
+    assert_eq!(
+        client.try_appeal(&contributor, &plan_id, &statement),
+        Err(Ok(SynapseError::AppealNotAllowed))
+    );
+    let cid = String::from_str(&env, "Qm");
+    let new_hash = make_content_hash(&env, 254);
+    assert_eq!(
+        client.try_update_plan_content(&contributor, &plan_id, &new_hash, &cid),
+        Err(Ok(SynapseError::PlanNotActive))
+    );
+}
+
+#[test]
+fn test_takedown_stops_earnings() {
+    let (env, client, admin, operator, native_token) = setup_env();
+    let author = Address::generate(&env);
+    let deriver = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    soroban_sdk::token::StellarAssetClient::new(&env, &native_token)
+        .mint(&buyer, &100_000_000_i128);
+    let token_client = soroban_sdk::token::Client::new(&env, &native_token);
+
+    let parent = make_plan_id(&env, 246);
+    let input = make_input(&env, parent.clone(), make_content_hash(&env, 246), "Parent", 80);
+    client.store_plan(&author, &input);
+    let child = make_plan_id(&env, 247);
+    let mut input = make_input(&env, child.clone(), make_content_hash(&env, 247), "Child", 80);
+    input.parent = Some(parent.clone());
+    client.store_plan(&deriver, &input);
+    client.set_royalty_bps(&1_000u32);
+
+    // Recalled under a pass, then taken down
+    client.set_subscription(&Some(SubscriptionProduct {
+        token: native_token.clone(),
+        price: 10_000_000,
+        duration: 500,
+    }));
+    client.buy_subscription(&buyer);
+    client.purchase_plan(&buyer, &parent, &native_token, &0_i128, &None);
+    client.takedown(&admin, &parent, &2u32);
+
+    let query = BytesN::from_array(&env, &[1u8; 32]);
+    let results = Vec::from_array(&env, [parent.clone()]);
+    assert_eq!(
+        client.try_pay_search(&buyer, &query, &results).unwrap_err(),
+        Ok(SynapseError::PlanNotActive)
+    );
+
+    // The parent's pool share is forfeited to the operator
+    env.ledger().with_mut(|li| li.sequence_number = 500);
+    let ids = Vec::from_array(&env, [parent.clone()]);
+    assert_eq!(client.distribute_subscription(&0, &native_token, &ids), 7_000_000);
+    assert_eq!(client.get_claimable(&author, &native_token), 0);
+    assert_eq!(client.get_claimable(&operator, &native_token), 10_000_000);
+
+    // Derivatives no longer pay it royalties
+    client.purchase_plan(&buyer, &child, &native_token, &10_000_000_i128, &None);
+    assert_eq!(token_client.balance(&author), 0);
+    assert_eq!(token_client.balance(&deriver), 7_000_000);
+}
+
+#[test]
+fn test_flagging_quarantines_plan() {
+    let (env, client, _admin, _operator, native_token) = setup_env();
+    let contributor = Address::generate(&env);
+    let plan_id = make_plan_id(&env, 255);
+    let input = make_input(&env, plan_id.clone(), make_content_hash(&env, 255), "Dud", 80);
+    client.store_plan(&contributor, &input);
+    client.set_flag_threshold(&2u32);
+    assert_eq!(client.get_flag_threshold(), 2);
+
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(&env, &native_token);
+    let buyers = [
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    for buyer in buyers.iter() {
+        token_admin_client.mint(buyer, &10_000_000_i128);
+    }
+    client.purchase_plan(&buyers[0], &plan_id, &native_token, &10_000_000_i128, &None);
+    client.purchase_plan(&buyers[1], &plan_id, &native_token, &10_000_000_i128, &None);
+
+    // Only buyers may flag, and only once
+    assert_eq!(
+        client.try_flag_plan(&buyers[2], &plan_id, &1u32),
+        Err(Ok(SynapseError::NotPurchased))
+    );
+    assert_eq!(client.flag_plan(&buyers[0], &plan_id, &1u32), 1);
+    assert_eq!(env.auths()[0].0, buyers[0]);
+    assert_eq!(
+        client.try_flag_plan(&buyers[0], &plan_id, &1u32),
+        Err(Ok(SynapseError::AlreadyFlagged))
+    );
+    assert_eq!(client.get_plan_status(&plan_id), PlanStatus::Active);
+
+    // The threshold-th flag quarantines the plan and halts sales
+    assert_eq!(client.flag_plan(&buyers[1], &plan_id, &7u32), 2);
+    assert_eq!(client.get_flag_count(&plan_id), 2);
+    assert_eq!(client.get_plan_status(&plan_id), PlanStatus::Quarantined);
+    let flags = client.get_flags(&plan_id);
+    assert_eq!((flags.get(1).unwrap().buyer, flags.get(1).unwrap().reason), (buyers[1].clone(), 7));
+    let amount = 10_000_000_i128;
+    let result = client.try_purchase_plan(&buyers[2], &plan_id, &native_token, &amount, &None);
+    assert_eq!(result.unwrap_err(), Ok(SynapseError::PlanNotActive));
+    assert_eq!(
+        client.try_deprecate_plan(&contributor, &plan_id, &None),
+        Err(Ok(SynapseError::InvalidStatusTransition))
+    );
+
+    // The admin reviews and releases it; only later flags count again
+    client.activate_plan(&plan_id);
+    client.purchase_plan(&buyers[2], &plan_id, &native_token, &10_000_000_i128, &None);
+    client.purchase_plan(&buyers[3], &plan_id, &native_token, &10_000_000_i128, &None);
+    assert_eq!(client.flag_plan(&buyers[2], &plan_id, &1u32), 3);
+    assert_eq!(client.get_plan_status(&plan_id), PlanStatus::Active);
+    assert_eq!(client.flag_plan(&buyers[3], &plan_id, &1u32), 4);
+    assert_eq!(client.get_plan_status(&plan_id), PlanStatus::Quarantined);
+}
@@ -0,0 +1,70 @@
+use soroban_sdk::{Address, BytesN, Env, Vec};
+
+use crate::admin::get_native_token;
+use crate::errors::SynapseError;
+use crate::storage_keys::DataKey;
+use crate::types::PlanMeta;
+
+/// Token contracts purchases may be paid in. Deployments that predate the
+/// allowlist accept only the native token.
+pub fn get_accepted_tokens(env: &Env) -> Result<Vec<Address>, SynapseError> {
+    match env.storage().instance().get(&DataKey::AcceptedTokens) {
+        Some(tokens) => Ok(tokens),
+        None => Ok(Vec::from_array(env, [get_native_token(env)?])),
+    }
+}
+
+pub fn set_accepted_tokens(env: &Env, tokens: &Vec<Address>) {
+    env.storage()
+        .instance()
+        .set(&DataKey::AcceptedTokens, tokens);
+}
+
+pub fn require_accepted(env: &Env, token: &Address) -> Result<(), SynapseError> {
+    if !get_accepted_tokens(env)?.contains(token) {
+        return Err(SynapseError::TokenNotAccepted);
+    }
+    Ok(())
+}
+
+/// The (floor, ceiling) for plan prices in a non-native token. Unlike the
+/// native bounds these have no default: the admin sets them per asset.
+pub fn get_price_bounds(env: &Env, token: &Address) -> Option<(i128, i128)> {
+    env.storage()
+        .instance()
+        .get(&DataKey::TokenPriceBounds(token.clone()))
+}
+
+pub fn set_price_bounds(env: &Env, token: &Address, min_price: i128, max_price: i128) {
+    env.storage().instance().set(
+        &DataKey::TokenPriceBounds(token.clone()),
+        &(min_price, max_price),
+    );
+}
+
+/// A token without admin bounds cannot be priced in.
+pub fn validate_price(env: &Env, token: &Address, price: i128) -> Result<(), SynapseError> {
+    let (min, max) = get_price_bounds(env, token).ok_or(SynapseError::TokenBoundsNotSet)?;
+    if price < min || price > max {
+        return Err(SynapseError::PriceOutOfBounds);
+    }
+    Ok(())
+}
+
+/// A plan's price in `token`. The native-token price lives on `PlanMeta`;
+/// prices in other assets are set separately by the contributor.
+pub fn get_plan_price(env: &Env, plan: &PlanMeta, token: &Address) -> Result<i128, SynapseError> {
+    if *token == get_native_token(env)? {
+        return Ok(plan.price);
+    }
+    env.storage()
+        .persistent()
+        .get(&DataKey::PlanPrice(plan.id.clone(), token.clone()))
+        .ok_or(SynapseError::TokenNotPriced)
+}
+
+pub fn set_plan_price(env: &Env, plan_id: &BytesN<16>, token: &Address, price: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PlanPrice(plan_id.clone(), token.clone()), &price);
+}
@@ -8,6 +8,34 @@ pub enum StorageTier {
     Archive,
 }
 
+/// State-changing operations that can be halted by `pause`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PausableOp {
+    StorePlan,
+    PurchasePlan,
+    SetTier,
+    PaySearch,
+}
+
+/// Which party receives the sub-stroop remainder of a revenue split.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DustRecipient {
+    Contributor,
+    Operator,
+}
+
+/// How purchase revenue reaches contributors and the operator.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SettlementMode {
+    /// Transfer each share to its recipient during the purchase.
+    Push,
+    /// Hold the payment in the contract and credit claimable balances.
+    Accrue,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct PlanMeta {
@@ -22,18 +50,173 @@ pub struct PlanMeta {
     pub framework: String,
     pub contributor: Address,
     pub quality_score: u32,
+    pub price: i128,
     pub purchase_count: u32,
     pub tier: StorageTier,
     pub created_at: u64,
 }
 
+/// How long a purchase grants access to a plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LicenseModel {
+    /// Access never expires; the plan is bought once.
+    Perpetual,
+    /// Access lasts this many ledgers and can be renewed by buying again.
+    Duration(u32),
+}
+
+/// The flat-fee pass buyers can purchase instead of paying per plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionProduct {
+    pub token: Address,
+    pub price: i128,
+    /// Ledgers a pass lasts; also the length of an accounting period.
+    pub duration: u32,
+}
+
+/// A buyer's subscription pass.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionPass {
+    /// First ledger the pass no longer covers.
+    pub expires_at_ledger: u32,
+    /// Token the pass was last paid in.
+    pub token: Address,
+}
+
+/// Proof that a buyer paid for a search.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchReceipt {
+    pub id: u64,
+    pub buyer: Address,
+    /// Hash of the query text; the query itself stays off-chain.
+    pub query_hash: BytesN<32>,
+    pub result_plan_ids: Vec<BytesN<16>>,
+    pub fee: i128,
+    /// Part of `fee` credited to the result plans' beneficiaries.
+    pub contributor_share: i128,
+    pub token: Address,
+    pub ledger: u32,
+}
+
+/// A buyer's right to access a plan.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entitlement {
+    /// Ledger of the most recent purchase or renewal.
+    pub granted_at_ledger: u32,
+    /// First ledger without access, or `None` for perpetual access.
+    pub expires_at_ledger: Option<u32>,
+}
+
+/// Whether a plan is listed for sale.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlanStatus {
+    /// Awaiting admin approval before it can be bought.
+    Pending,
+    Active,
+    /// Withdrawn by its contributor, optionally pointing at a replacement.
+    Deprecated(Option<BytesN<16>>),
+    /// Delisted by the admin, with a policy reason code.
+    Removed(u32),
+    /// Held for moderator review after reaching the buyer flag threshold.
+    Quarantined,
+}
+
+/// Where a contributor's appeal against a takedown stands.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AppealStatus {
+    NotFiled,
+    /// Awaiting the admin, with a hash of the contributor's statement.
+    Pending(BytesN<32>),
+    /// The takedown is final.
+    Rejected,
+}
+
+/// A buyer's report against a plan they bought.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanFlag {
+    pub buyer: Address,
+    /// Off-chain reason code.
+    pub reason: u32,
+    pub ledger: u32,
+}
+
+/// Audit record of a moderation takedown.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Takedown {
+    pub moderator: Address,
+    /// Off-chain policy reason code.
+    pub reason: u32,
+    pub ledger: u32,
+    pub appeal: AppealStatus,
+    /// Status the plan had before the takedown, restored on a successful appeal.
+    pub prior_status: PlanStatus,
+}
+
+/// Descriptive fields to change on a plan. `None` leaves a field as is.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanMetaUpdate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub domain: Option<String>,
+    pub language: Option<String>,
+    pub framework: Option<String>,
+}
+
+/// One revision of a plan's content. Version 1 is the content the plan was
+/// stored with.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlanVersion {
+    pub version: u32,
+    pub content_hash: BytesN<32>,
+    pub ipfs_cid: String,
+    pub created_at: u64,
+}
+
+/// A co-author of a plan and their weight of the contributor share.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Beneficiary {
+    pub address: Address,
+    pub weight_bps: u32,
+}
+
+/// One transfer of the contributor share made for a purchase.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payout {
+    pub recipient: Address,
+    pub amount: i128,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct PurchaseRecord {
     pub buyer: Address,
     pub amount_stroops: i128,
     pub contributor_share: i128,
+    /// Operator share net of the referral share.
     pub operator_share: i128,
+    /// How the contributor share was divided between beneficiaries and
+    /// upstream royalties.
+    pub payouts: Vec<Payout>,
+    /// Token contract the purchase was paid in.
+    pub token: Address,
+    /// Agent that surfaced the plan to the buyer, if any.
+    pub referrer: Option<Address>,
+    /// Part of the operator share paid to the referrer.
+    pub referral_share: i128,
     pub ledger: u32,
 }
 
@@ -44,11 +227,57 @@ pub struct KBStatsResult {
     pub total_purchases: u32,
 }
 
+/// A proposed contributor share change, executable from `effective_ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShareChange {
+    pub new_share_bps: u32,
+    pub proposed_at_ledger: u32,
+    pub effective_ledger: u32,
+}
+
+/// Funds a buyer escrowed for off-chain voucher payments.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Channel {
+    pub id: u64,
+    /// ed25519 public key that signs the buyer's vouchers.
+    pub signer: BytesN<32>,
+    pub deposit: i128,
+    /// Total paid out of `deposit` so far.
+    pub redeemed: i128,
+    /// Number of voucher plan IDs already settled.
+    pub settled: u32,
+    /// Nonce of the last redeemed voucher.
+    pub nonce: u64,
+    /// Last ledger vouchers can be redeemed, once the buyer starts closing.
+    pub closes_at: Option<u32>,
+}
+
+/// A cumulative payment promise signed off-chain by a channel's signer. Each
+/// voucher lists every plan bought on the channel so far, and `total` is the
+/// most the buyer agrees to pay for all of them.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Voucher {
+    /// The contract the voucher is valid on.
+    pub contract: Address,
+    pub channel_id: u64,
+    pub buyer: Address,
+    pub token: Address,
+    /// Must increase with every voucher redeemed.
+    pub nonce: u64,
+    pub plan_ids: Vec<BytesN<16>>,
+    pub total: i128,
+}
+
 /// Input struct for store_plan to stay under the 10-param Soroban limit.
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct StorePlanInput {
-    pub id: BytesN<16>,
+    /// Caller-chosen plan ID, or `None` to have the contract derive one
+    /// from the content hash and contributor.
+    pub id: Option<BytesN<16>>,
     pub title: String,
     pub description: String,
     pub content_hash: BytesN<32>,
@@ -58,4 +287,12 @@ pub struct StorePlanInput {
     pub language: String,
     pub framework: String,
     pub quality_score: u32,
+    /// Price per purchase in stroops; must lie within the admin price bounds.
+    pub price: i128,
+    /// Co-authors sharing the contributor share, with weights summing to
+    /// 10000 bps. Empty means the contributor receives all of it.
+    pub beneficiaries: Vec<Beneficiary>,
+    pub license: LicenseModel,
+    /// Plan this one extends, which earns royalties on its purchases.
+    pub parent: Option<BytesN<16>>,
 }
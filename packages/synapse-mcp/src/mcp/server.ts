@@ -174,42 +174,49 @@ export async function startMcpServer(): Promise<void> {
           };
         }
 
-        // Submit payment
-        const payment = await submitPayment(
-          keypair,
-          PLATFORM_ADDRESS,
-          RECALL_COST_XLM,
-        );
+        // Plans the agent already owns are not paid for again
+        const owned = await storage.hasAccess(id, publicKey);
+        let txHash: string | null = null;
+
+        if (!owned) {
+          const payment = await submitPayment(
+            keypair,
+            PLATFORM_ADDRESS,
+            RECALL_COST_XLM,
+          );
+
+          if (!payment.success) {
+            return {
+              content: [
+                {
+                  type: "text" as const,
+                  text: `Payment failed: ${payment.error}\nEnsure your wallet is funded. Run: synapse-mcp fund`,
+                },
+              ],
+              isError: true,
+            };
+          }
 
-        if (!payment.success) {
-          return {
-            content: [
-              {
-                type: "text" as const,
-                text: `Payment failed: ${payment.error}\nEnsure your wallet is funded. Run: synapse-mcp fund`,
-              },
-            ],
-            isError: true,
-          };
+          // Record purchase
+          await storage.recordPurchase(
+            id,
+            publicKey,
+            10_000_000, // 1 XLM in stroops
+            payment.txHash,
+          );
+          txHash = payment.txHash;
         }
 
-        // Record purchase
-        await storage.recordPurchase(
-          id,
-          publicKey,
-          10_000_000, // 1 XLM in stroops
-          payment.txHash,
-        );
-
         appendHistory({
           action: "recall",
           planId: id,
-          txHash: payment.txHash || undefined,
-          costXlm: 1,
+          txHash: txHash || undefined,
+          costXlm: owned ? 0 : 1,
         });
 
         const balance = await getBalance(publicKey).catch(() => "unknown");
         const tags = JSON.parse(plan.tags) as string[];
+        const cost = owned ? "0 XLM (already owned)" : `${RECALL_COST_XLM} XLM`;
 
         const text = [
           `# ${plan.title}`,
@@ -226,7 +233,7 @@ export async function startMcpServer(): Promise<void> {
             { type: "text" as const, text },
             {
               type: "text" as const,
-              text: `\nCost: ${RECALL_COST_XLM} XLM | Tx: ${payment.txHash || "n/a"}\nWallet: ${publicKey} | Balance: ${balance}`,
+              text: `\nCost: ${cost} | Tx: ${txHash || "n/a"}\nWallet: ${publicKey} | Balance: ${balance}`,
             },
           ],
         };
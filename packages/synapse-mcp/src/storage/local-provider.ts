@@ -75,6 +75,11 @@ export class LocalStorageProvider implements StorageProvider {
     );
   }
 
+  async hasAccess(_planId: string, _buyerAddress: string): Promise<boolean> {
+    // Local purchases grant no entitlement; every recall is paid
+    return false;
+  }
+
   async getContributorStats(address: string): Promise<ContributorStats> {
     return this.planStore.getContributorStats(address);
   }
@@ -36,6 +36,7 @@ export interface StorageProvider {
     amountStroops: number,
     txHash: string | null,
   ): Promise<Purchase>;
+  hasAccess(planId: string, buyerAddress: string): Promise<boolean>;
   getContributorStats(address: string): Promise<ContributorStats>;
   getKBStats(): Promise<KBStats>;
 
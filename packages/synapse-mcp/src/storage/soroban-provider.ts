@@ -1,11 +1,13 @@
 import * as StellarSdk from "@stellar/stellar-sdk";
 const {
+  Asset,
   Contract,
   Keypair,
   Networks,
   rpc: SorobanRpc,
   TransactionBuilder,
   nativeToScVal,
+  scValToNative,
   xdr,
   Address,
 } = StellarSdk;
@@ -28,6 +30,9 @@ import type {
 import type { IPFSClient } from "../ipfs/client.js";
 import type { SorobanEventIndexer } from "../indexer/event-listener.js";
 
+// Default plan price: 1 XLM, the recall cost charged by the MCP server
+const DEFAULT_PRICE_STROOPS = 10_000_000;
+
 export class SorobanStorageProvider implements StorageProvider {
   private rpcServer: SorobanRpc.Server;
   private contract: Contract;
@@ -62,12 +67,6 @@ export class SorobanStorageProvider implements StorageProvider {
     // Pin content to IPFS
     const { cid } = await this.ipfsClient.pin(plan.content);
 
-    // Generate plan ID (16 bytes)
-    const idBytes = createHash("md5")
-      .update(`${plan.title}-${Date.now()}-${Math.random()}`)
-      .digest();
-    const planId = idBytes.toString("hex");
-
     // SHA-256 content hash
     const contentHash = createHash("sha256")
       .update(plan.content)
@@ -75,15 +74,41 @@ export class SorobanStorageProvider implements StorageProvider {
 
     // Invoke store_plan on Soroban with StorePlanInput struct
     const contributorAddress = new Address(plan.contributor_address);
-    const idBytesN = Buffer.from(planId, "hex");
     const hashBytesN = Buffer.from(contentHash, "hex");
 
     const tags = plan.tags.map((t) =>
       nativeToScVal(t, { type: "string" }),
     );
 
+    // Co-author weights must sum to 10000 bps; none pays the contributor
+    const beneficiaries = (plan.beneficiaries ?? []).map((b) =>
+      xdr.ScVal.scvMap([
+        new xdr.ScMapEntry({
+          key: xdr.ScVal.scvSymbol("address"),
+          val: new Address(b.address).toScVal(),
+        }),
+        new xdr.ScMapEntry({
+          key: xdr.ScVal.scvSymbol("weight_bps"),
+          val: nativeToScVal(b.weight_bps, { type: "u32" }),
+        }),
+      ]),
+    );
+
+    // LicenseModel enum: Perpetual, or Duration(ledgers)
+    const license =
+      plan.license_ledgers === undefined
+        ? xdr.ScVal.scvVec([xdr.ScVal.scvSymbol("Perpetual")])
+        : xdr.ScVal.scvVec([
+            xdr.ScVal.scvSymbol("Duration"),
+            nativeToScVal(plan.license_ledgers, { type: "u32" }),
+          ]);
+
     // Build StorePlanInput struct as an ScVal map (sorted by field name)
     const inputStruct = xdr.ScVal.scvMap([
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol("beneficiaries"),
+        val: xdr.ScVal.scvVec(beneficiaries),
+      }),
       new xdr.ScMapEntry({
         key: xdr.ScVal.scvSymbol("content_hash"),
         val: xdr.ScVal.scvBytes(hashBytesN),
@@ -101,8 +126,9 @@ export class SorobanStorageProvider implements StorageProvider {
         val: nativeToScVal(plan.framework || "", { type: "string" }),
       }),
       new xdr.ScMapEntry({
+        // None: the contract derives the ID from content hash and contributor
         key: xdr.ScVal.scvSymbol("id"),
-        val: xdr.ScVal.scvBytes(idBytesN),
+        val: xdr.ScVal.scvVoid(),
       }),
       new xdr.ScMapEntry({
         key: xdr.ScVal.scvSymbol("ipfs_cid"),
@@ -112,6 +138,23 @@ export class SorobanStorageProvider implements StorageProvider {
         key: xdr.ScVal.scvSymbol("language"),
         val: nativeToScVal(plan.language || "", { type: "string" }),
       }),
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol("license"),
+        val: license,
+      }),
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol("parent"),
+        val: plan.parent_id
+          ? xdr.ScVal.scvBytes(Buffer.from(plan.parent_id, "hex"))
+          : xdr.ScVal.scvVoid(),
+      }),
+      new xdr.ScMapEntry({
+        key: xdr.ScVal.scvSymbol("price"),
+        val: nativeToScVal(
+          BigInt(plan.price_stroops ?? DEFAULT_PRICE_STROOPS),
+          { type: "i128" },
+        ),
+      }),
       new xdr.ScMapEntry({
         key: xdr.ScVal.scvSymbol("quality_score"),
         val: nativeToScVal(plan.quality_score ?? 0, { type: "u32" }),
@@ -132,7 +175,12 @@ export class SorobanStorageProvider implements StorageProvider {
       inputStruct,
     );
 
-    await this.submitTransaction(op);
+    const { result } = await this.submitTransaction(op);
+    const storedId = result?.bytes();
+    if (!storedId) {
+      throw new Error("store_plan returned no plan ID");
+    }
+    const planId = Buffer.from(storedId).toString("hex");
 
     return {
       id: planId,
@@ -223,30 +271,52 @@ export class SorobanStorageProvider implements StorageProvider {
   ): Promise<Purchase> {
     const buyer = new Address(buyerAddress);
     const planIdBytes = Buffer.from(planId, "hex");
+    // Pay in XLM through its Stellar Asset Contract
+    const token = new Address(Asset.native().contractId(this.networkPassphrase));
 
     const op = this.contract.call(
       "purchase_plan",
       buyer.toScVal(),
       xdr.ScVal.scvBytes(planIdBytes),
+      token.toScVal(),
       nativeToScVal(BigInt(amountStroops), { type: "i128" }),
+      xdr.ScVal.scvVoid(), // no referrer
     );
 
-    await this.submitTransaction(op);
+    const { result } = await this.submitTransaction(op);
 
-    const contributorShare = Math.floor((amountStroops * 70) / 100);
-    const operatorShare = amountStroops - contributorShare;
+    // The contract returns the PurchaseRecord with the shares it paid out
+    const record = result ? scValToNative(result) : null;
+    if (!record) {
+      throw new Error("purchase_plan returned no purchase record");
+    }
 
     return {
       plan_id: planId,
       buyer_address: buyerAddress,
-      amount_stroops: amountStroops,
-      contributor_share_stroops: contributorShare,
-      operator_share_stroops: operatorShare,
+      amount_stroops: Number(record.amount_stroops),
+      contributor_share_stroops: Number(record.contributor_share),
+      operator_share_stroops: Number(record.operator_share),
       transaction_hash: null,
       created_at: new Date().toISOString(),
     };
   }
 
+  async hasAccess(planId: string, buyerAddress: string): Promise<boolean> {
+    const op = this.contract.call(
+      "has_access",
+      new Address(buyerAddress).toScVal(),
+      xdr.ScVal.scvBytes(Buffer.from(planId, "hex")),
+    );
+
+    try {
+      const result = await this.simulateQuery(op);
+      return result?.b() ?? false;
+    } catch {
+      return false;
+    }
+  }
+
   async getContributorStats(address: string): Promise<ContributorStats> {
     const addr = new Address(address);
 
@@ -480,7 +550,11 @@ export class SorobanStorageProvider implements StorageProvider {
     return null;
   }
 
-  private async submitTransaction(op: xdr.Operation): Promise<string> {
+  // Returns the tx hash and the simulated return value, which execution
+  // reproduces on-chain.
+  private async submitTransaction(
+    op: xdr.Operation,
+  ): Promise<{ txHash: string; result: xdr.ScVal | null }> {
     const account = await this.rpcServer.getAccount(
       this.keypair.publicKey(),
     );
@@ -501,6 +575,8 @@ export class SorobanStorageProvider implements StorageProvider {
       );
     }
 
+    const result = simResult.result?.retval ?? null;
+
     const assembled = SorobanRpc.assembleTransaction(
       tx,
       simResult,
@@ -526,7 +602,7 @@ export class SorobanStorageProvider implements StorageProvider {
       try {
         const getResult = await this.rpcServer.getTransaction(txHash);
         if (getResult.status === SorobanRpc.Api.GetTransactionStatus.SUCCESS) {
-          return txHash;
+          return { txHash, result };
         }
         if (getResult.status !== SorobanRpc.Api.GetTransactionStatus.NOT_FOUND) {
           throw new Error(`Transaction failed: ${getResult.status}`);
@@ -537,7 +613,7 @@ export class SorobanStorageProvider implements StorageProvider {
         // If we get an XDR parse error after the tx was accepted, treat as success.
         const msg = pollError instanceof Error ? pollError.message : String(pollError);
         if (msg.includes("Bad union switch")) {
-          return txHash;
+          return { txHash, result };
         }
         throw pollError;
       }
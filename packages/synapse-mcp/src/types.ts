@@ -79,6 +79,10 @@ export interface StorePlanInput {
   framework?: string;
   contributor_address: string;
   quality_score?: number; // -1 = unscored, 0-100 = AI score
+  price_stroops?: number; // on-chain price, defaults to 1 XLM
+  beneficiaries?: { address: string; weight_bps: number }[]; // co-authors
+  license_ledgers?: number; // time-limited access length; perpetual if unset
+  parent_id?: string; // hex ID of the plan this one extends
 }
 
 export interface SorobanConfig {